@@ -0,0 +1,111 @@
+//! L1 to L2 address aliasing.
+//!
+//! The sender of an L1 message sent by an L1 contract is aliased on L2 by adding the
+//! [`L1_TO_L2_ALIAS_OFFSET`], such that it cannot impersonate the L2 contract deployed at the same
+//! address. L1 messages sent by EOAs are not aliased.
+
+use crate::error::ScrollInvalidTransaction;
+use std::vec::Vec;
+
+use revm::{
+    bytecode::Bytecode,
+    primitives::{address, aliases::U160, Address},
+};
+
+/// The offset added to the address of L1 contracts sending L1 messages.
+pub const L1_TO_L2_ALIAS_OFFSET: Address = address!("0x1111000000000000000000000000000000001111");
+
+/// Returns the L2 alias of an L1 contract address.
+pub fn apply_l1_to_l2_alias(address: Address) -> Address {
+    Address::from(to_u160(address).wrapping_add(to_u160(L1_TO_L2_ALIAS_OFFSET)).to_be_bytes::<20>())
+}
+
+/// Returns the L1 contract address of an L2 alias.
+pub fn undo_l1_to_l2_alias(address: Address) -> Address {
+    Address::from(to_u160(address).wrapping_sub(to_u160(L1_TO_L2_ALIAS_OFFSET)).to_be_bytes::<20>())
+}
+
+fn to_u160(address: Address) -> U160 {
+    U160::from_be_slice(address.as_slice())
+}
+
+/// The strict validation of L1 message senders.
+///
+/// The handler rejects L1 messages sent by a known L1 contract whose address was not aliased, and
+/// L1 messages whose sender has code deployed on L2, with a dedicated [`ScrollInvalidTransaction`]
+/// instead of the generic `RejectCallerWithCode`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrictL1Sender {
+    /// The L1 contracts sending L1 messages, e.g. the L1 messenger, which must be aliased.
+    pub l1_contracts: Vec<Address>,
+}
+
+impl StrictL1Sender {
+    /// Returns a new [`StrictL1Sender`] for the provided L1 contracts.
+    pub fn new(l1_contracts: Vec<Address>) -> Self {
+        Self { l1_contracts }
+    }
+
+    /// Validates the sender of an L1 message with the provided L2 code. Delegated EOAs are
+    /// allowed.
+    pub fn validate(
+        &self,
+        sender: Address,
+        code: Option<&Bytecode>,
+    ) -> Result<(), ScrollInvalidTransaction> {
+        if self.l1_contracts.contains(&sender) {
+            return Err(ScrollInvalidTransaction::UnaliasedL1Sender { sender });
+        }
+        if code.is_some_and(|code| !code.is_empty() && !code.is_eip7702()) {
+            return Err(ScrollInvalidTransaction::L1SenderWithCode { sender });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::bytecode::LegacyRawBytecode;
+    use rstest::rstest;
+    use std::vec;
+
+    #[rstest]
+    #[case(Address::ZERO, L1_TO_L2_ALIAS_OFFSET)]
+    // the L1 messenger.
+    #[case(
+        address!("0x6774Bcbd5ceCeF1336b5300fb5186a12DDD8b367"),
+        address!("0x7885BcBd5CeCEf1336b5300fb5186A12DDD8c478")
+    )]
+    // the alias wraps around.
+    #[case(
+        address!("0xffffffffffffffffffffffffffffffffffffffff"),
+        address!("0x1111000000000000000000000000000000001110")
+    )]
+    fn test_l1_to_l2_alias(#[case] l1_address: Address, #[case] l2_address: Address) {
+        assert_eq!(apply_l1_to_l2_alias(l1_address), l2_address);
+        assert_eq!(undo_l1_to_l2_alias(l2_address), l1_address);
+    }
+
+    #[test]
+    fn test_strict_l1_sender() {
+        let messenger = address!("0x6774Bcbd5ceCeF1336b5300fb5186a12DDD8b367");
+        let strict = StrictL1Sender::new(vec![messenger]);
+
+        assert_eq!(strict.validate(apply_l1_to_l2_alias(messenger), None), Ok(()));
+        assert_eq!(
+            strict.validate(messenger, None),
+            Err(ScrollInvalidTransaction::UnaliasedL1Sender { sender: messenger })
+        );
+
+        let sender = apply_l1_to_l2_alias(messenger);
+        let code = Bytecode::LegacyAnalyzed(LegacyRawBytecode([1u8; 2].into()).into_analyzed());
+        assert_eq!(
+            strict.validate(sender, Some(&code)),
+            Err(ScrollInvalidTransaction::L1SenderWithCode { sender })
+        );
+        let delegation = Bytecode::new_eip7702(messenger);
+        assert_eq!(strict.validate(sender, Some(&delegation)), Ok(()));
+    }
+}
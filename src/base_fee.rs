@@ -0,0 +1,189 @@
+//! L2 base fee of Scroll blocks.
+//!
+//! The base fee is enabled from CURIE and derived from the L1 base fee recorded in the L1 gas
+//! price oracle at the parent block:
+//!
+//! `base_fee = min(l1_base_fee * scalar / BASE_FEE_PRECISION + overhead, MAXIMUM_L2_BASE_FEE)`
+//!
+//! The scalar and overhead are configured per fork through an [`L2BaseFeeConfig`], which has no
+//! default such that chains always provide their parameters explicitly.
+//! [`CURIE_L2_BASE_FEE_PARAMS`] are the Scroll mainnet parameters from CURIE to FEYNMAN.
+
+use crate::{l1block::L1BlockInfo, ScrollSpecId};
+use core::fmt;
+
+use revm::{context::Block, primitives::U256};
+
+/// The protocol enforced maximum L2 base fee (10 Gwei).
+pub const MAXIMUM_L2_BASE_FEE: u64 = 10_000_000_000;
+
+/// The precision of the L2 base fee scalar.
+pub const BASE_FEE_PRECISION: U256 = U256::from_limbs([1_000_000_000_000_000_000u64, 0, 0, 0]);
+
+/// The parameters of the L2 base fee formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct L2BaseFeeParams {
+    /// The scalar applied to the L1 base fee, scaled by [`BASE_FEE_PRECISION`].
+    pub scalar: U256,
+    /// The overhead added to the scaled L1 base fee, in wei.
+    pub overhead: U256,
+}
+
+/// The Scroll mainnet L2 base fee parameters from CURIE to FEYNMAN: the L1 verification fee
+/// `l1_base_fee * 0.0034`, the L2 sequencer fee (0.001 Gwei) and the proving fee (0.0337 Gwei).
+pub const CURIE_L2_BASE_FEE_PARAMS: L2BaseFeeParams = L2BaseFeeParams {
+    scalar: U256::from_limbs([3_400_000_000_000_000u64, 0, 0, 0]),
+    overhead: U256::from_limbs([34_700_000u64, 0, 0, 0]),
+};
+
+/// The L2 base fee configuration of a chain, with the parameters of each fork changing the L2 base
+/// fee formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct L2BaseFeeConfig {
+    /// The L2 base fee parameters from CURIE to FEYNMAN.
+    pub curie: L2BaseFeeParams,
+    /// The L2 base fee parameters from FEYNMAN.
+    pub feynman: L2BaseFeeParams,
+}
+
+impl L2BaseFeeConfig {
+    /// Returns a new [`L2BaseFeeConfig`] with the provided parameters of each fork.
+    pub fn new(curie: L2BaseFeeParams, feynman: L2BaseFeeParams) -> Self {
+        Self { curie, feynman }
+    }
+
+    /// Returns the L2 base fee parameters for the spec, None before CURIE.
+    pub fn params(&self, spec_id: ScrollSpecId) -> Option<L2BaseFeeParams> {
+        if !spec_id.is_enabled_in(ScrollSpecId::CURIE) {
+            None
+        } else if !spec_id.is_enabled_in(ScrollSpecId::FEYNMAN) {
+            Some(self.curie)
+        } else {
+            Some(self.feynman)
+        }
+    }
+
+    /// Returns the expected base fee of a block from the [`L1BlockInfo`] of its parent, None
+    /// before CURIE where the base fee is disabled.
+    pub fn next_block_base_fee(
+        &self,
+        spec_id: ScrollSpecId,
+        parent_l1_block_info: &L1BlockInfo,
+    ) -> Option<u64> {
+        let L2BaseFeeParams { scalar, overhead } = self.params(spec_id)?;
+        let base_fee = parent_l1_block_info
+            .l1_base_fee
+            .saturating_mul(scalar)
+            .wrapping_div(BASE_FEE_PRECISION)
+            .saturating_add(overhead);
+        Some(base_fee.min(U256::from(MAXIMUM_L2_BASE_FEE)).to())
+    }
+
+    /// Validates the base fee of the block against the base fee expected from the
+    /// [`L1BlockInfo`] of its parent. Blocks before CURIE must have a zero base fee.
+    pub fn validate_base_fee(
+        &self,
+        spec_id: ScrollSpecId,
+        block: &impl Block,
+        parent_l1_block_info: &L1BlockInfo,
+    ) -> Result<(), InvalidBaseFee> {
+        let expected = self.next_block_base_fee(spec_id, parent_l1_block_info).unwrap_or_default();
+        let got = block.basefee();
+        if got != expected {
+            return Err(InvalidBaseFee { expected, got });
+        }
+        Ok(())
+    }
+}
+
+/// The base fee of a block does not match the expected L2 base fee.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InvalidBaseFee {
+    /// The expected base fee.
+    pub expected: u64,
+    /// The base fee of the block.
+    pub got: u64,
+}
+
+impl core::error::Error for InvalidBaseFee {}
+
+impl fmt::Display for InvalidBaseFee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid base fee: expected {}, got {}", self.expected, self.got)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::context::BlockEnv;
+    use rstest::rstest;
+
+    const GWEI: u64 = 1_000_000_000;
+
+    fn l1_block_info(l1_base_fee: u64) -> L1BlockInfo {
+        L1BlockInfo { l1_base_fee: U256::from(l1_base_fee), ..Default::default() }
+    }
+
+    fn config() -> L2BaseFeeConfig {
+        L2BaseFeeConfig::new(
+            CURIE_L2_BASE_FEE_PARAMS,
+            L2BaseFeeParams {
+                scalar: U256::from(10_000_000_000_000_000u64),
+                overhead: U256::from(15_000_000),
+            },
+        )
+    }
+
+    #[rstest]
+    #[case(ScrollSpecId::SHANGHAI, 10 * GWEI, None)]
+    #[case(ScrollSpecId::BERNOULLI, 10 * GWEI, None)]
+    // 10 Gwei * 0.0034 + 0.0347 Gwei
+    #[case(ScrollSpecId::CURIE, 10 * GWEI, Some(68_700_000))]
+    #[case(ScrollSpecId::EUCLID, 10 * GWEI, Some(68_700_000))]
+    #[case(ScrollSpecId::CURIE, 0, Some(34_700_000))]
+    // 10 Gwei * 0.01 + 0.015 Gwei
+    #[case(ScrollSpecId::FEYNMAN, 10 * GWEI, Some(115_000_000))]
+    #[case(ScrollSpecId::GALILEO, 10 * GWEI, Some(115_000_000))]
+    // capped at the maximum L2 base fee.
+    #[case(ScrollSpecId::CURIE, 10_000_000 * GWEI, Some(MAXIMUM_L2_BASE_FEE))]
+    #[case(ScrollSpecId::FEYNMAN, u64::MAX, Some(MAXIMUM_L2_BASE_FEE))]
+    fn test_next_block_base_fee(
+        #[case] spec_id: ScrollSpecId,
+        #[case] l1_base_fee: u64,
+        #[case] expected: Option<u64>,
+    ) {
+        let base_fee = config().next_block_base_fee(spec_id, &l1_block_info(l1_base_fee));
+        assert_eq!(base_fee, expected);
+    }
+
+    #[test]
+    fn test_next_block_base_fee_curie_params() {
+        // the CURIE parameters are taken from the configuration.
+        let config = L2BaseFeeConfig {
+            curie: L2BaseFeeParams { scalar: BASE_FEE_PRECISION, overhead: U256::from(1) },
+            ..config()
+        };
+        let parent = l1_block_info(GWEI);
+
+        assert_eq!(config.next_block_base_fee(ScrollSpecId::CURIE, &parent), Some(GWEI + 1));
+        assert_eq!(config.next_block_base_fee(ScrollSpecId::FEYNMAN, &parent), Some(25_000_000));
+    }
+
+    #[test]
+    fn test_validate_base_fee() {
+        let config = config();
+        let parent = l1_block_info(10 * GWEI);
+
+        let block = BlockEnv { basefee: 68_700_000, ..Default::default() };
+        assert_eq!(config.validate_base_fee(ScrollSpecId::CURIE, &block, &parent), Ok(()));
+        assert_eq!(
+            config.validate_base_fee(ScrollSpecId::FEYNMAN, &block, &parent),
+            Err(InvalidBaseFee { expected: 115_000_000, got: 68_700_000 })
+        );
+        assert_eq!(
+            config.validate_base_fee(ScrollSpecId::BERNOULLI, &block, &parent),
+            Err(InvalidBaseFee { expected: 0, got: 68_700_000 })
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Block level rules of Scroll.
+//!
+//! The L1 messages included in a block must come before all L2 transactions, have contiguous queue
+//! indices continuing the L1 message queue from the parent block and their total gas limit must
+//! fit in the block.
+
+use crate::transaction::ScrollTxTr;
+use core::fmt;
+
+use revm::context::Block;
+
+/// An error that occurred while validating the L1 messages of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum L1MessageOrderError {
+    /// The L1 message at the index follows an L2 transaction.
+    L1MessageAfterL2Transaction {
+        /// The index of the transaction.
+        index: usize,
+    },
+    /// The L1 message at the index has no queue index.
+    MissingQueueIndex {
+        /// The index of the transaction.
+        index: usize,
+    },
+    /// The queue index of the L1 message at the index does not continue the L1 message queue.
+    UnexpectedQueueIndex {
+        /// The index of the transaction.
+        index: usize,
+        /// The expected queue index.
+        expected: u64,
+        /// The queue index of the L1 message.
+        got: u64,
+    },
+    /// The total gas limit of the L1 messages up to the index exceeds the block gas limit.
+    GasLimitExceeded {
+        /// The index of the transaction.
+        index: usize,
+        /// The total gas limit of the L1 messages up to the transaction.
+        gas_limit: u64,
+        /// The gas limit of the block.
+        block_gas_limit: u64,
+    },
+}
+
+impl core::error::Error for L1MessageOrderError {}
+
+impl fmt::Display for L1MessageOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::L1MessageAfterL2Transaction { index } => {
+                write!(f, "L1 message at index {index} follows an L2 transaction")
+            }
+            Self::MissingQueueIndex { index } => {
+                write!(f, "L1 message at index {index} has no queue index")
+            }
+            Self::UnexpectedQueueIndex { index, expected, got } => write!(
+                f,
+                "L1 message at index {index} has queue index {got}, expected {expected}"
+            ),
+            Self::GasLimitExceeded { index, gas_limit, block_gas_limit } => write!(
+                f,
+                "L1 messages up to index {index} use {gas_limit} gas, exceeding the block gas limit {block_gas_limit}"
+            ),
+        }
+    }
+}
+
+/// Validates the L1 messages of the transactions of a block before execution.
+///
+/// `next_queue_index` is the queue index following the last L1 message included before the
+/// block. Returns the queue index following the last L1 message of the block.
+pub fn validate_l1_messages<'a, TX: ScrollTxTr + 'a>(
+    block: &impl Block,
+    txs: impl IntoIterator<Item = &'a TX>,
+    next_queue_index: u64,
+) -> Result<u64, L1MessageOrderError> {
+    let block_gas_limit = block.gas_limit();
+    let mut next_queue_index = next_queue_index;
+    let mut gas_limit = 0u64;
+    let mut seen_l2_tx = false;
+
+    for (index, tx) in txs.into_iter().enumerate() {
+        if !tx.is_l1_msg() {
+            seen_l2_tx = true;
+            continue;
+        }
+        if seen_l2_tx {
+            return Err(L1MessageOrderError::L1MessageAfterL2Transaction { index });
+        }
+
+        let queue_index =
+            tx.queue_index().ok_or(L1MessageOrderError::MissingQueueIndex { index })?;
+        if queue_index != next_queue_index {
+            return Err(L1MessageOrderError::UnexpectedQueueIndex {
+                index,
+                expected: next_queue_index,
+                got: queue_index,
+            });
+        }
+        next_queue_index += 1;
+
+        gas_limit = gas_limit.saturating_add(tx.gas_limit());
+        if gas_limit > block_gas_limit {
+            return Err(L1MessageOrderError::GasLimitExceeded {
+                index,
+                gas_limit,
+                block_gas_limit,
+            });
+        }
+    }
+
+    Ok(next_queue_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{ScrollTransaction, TxL1Message};
+    use revm::context::{BlockEnv, TxEnv};
+
+    fn l1_message(queue_index: u64) -> ScrollTransaction<TxEnv> {
+        TxL1Message { queue_index, gas_limit: 100_000, ..Default::default() }.into()
+    }
+
+    fn block() -> BlockEnv {
+        BlockEnv { gas_limit: 250_000, ..Default::default() }
+    }
+
+    #[test]
+    fn test_validate_l1_messages() {
+        let l2_tx = ScrollTransaction::<TxEnv>::default();
+
+        let txs = [l1_message(5), l1_message(6), l2_tx.clone()];
+        assert_eq!(validate_l1_messages(&block(), &txs, 5), Ok(7));
+        assert_eq!(validate_l1_messages(&block(), &txs[2..], 5), Ok(5));
+
+        let txs = [l1_message(5), l2_tx.clone(), l1_message(6)];
+        assert_eq!(
+            validate_l1_messages(&block(), &txs, 5),
+            Err(L1MessageOrderError::L1MessageAfterL2Transaction { index: 2 })
+        );
+
+        let txs = [l1_message(5), l1_message(7)];
+        assert_eq!(
+            validate_l1_messages(&block(), &txs, 5),
+            Err(L1MessageOrderError::UnexpectedQueueIndex { index: 1, expected: 6, got: 7 })
+        );
+        assert_eq!(
+            validate_l1_messages(&block(), &txs, 4),
+            Err(L1MessageOrderError::UnexpectedQueueIndex { index: 0, expected: 4, got: 5 })
+        );
+
+        let mut txs = [l1_message(5), l1_message(6)];
+        txs[1].queue_index = None;
+        assert_eq!(
+            validate_l1_messages(&block(), &txs, 5),
+            Err(L1MessageOrderError::MissingQueueIndex { index: 1 })
+        );
+
+        let txs = [l1_message(5), l1_message(6), l1_message(7)];
+        assert_eq!(
+            validate_l1_messages(&block(), &txs, 5),
+            Err(L1MessageOrderError::GasLimitExceeded {
+                index: 2,
+                gas_limit: 300_000,
+                block_gas_limit: 250_000
+            })
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{
-    evm::ScrollEvm, instructions::ScrollInstructions, l1block::L1BlockInfo,
-    transaction::ScrollTxTr, ScrollSpecId, ScrollTransaction,
+    chain::ScrollChainContext,
+    evm::ScrollEvm,
+    instructions::ScrollInstructions,
+    rollup_fee::{RollupFeeModel, ScrollRollupFeeModel},
+    transaction::ScrollTxTr,
+    ScrollSpecId, ScrollTransaction,
 };
 
 use revm::{
@@ -25,14 +29,15 @@ pub trait ScrollBuilder: Sized {
     ) -> ScrollEvm<Self::Context, INSP, ScrollInstructions<EthInterpreter, Self::Context>>;
 }
 
-impl<BLOCK, TX, CFG, DB, JOURNAL> ScrollBuilder
-    for Context<BLOCK, TX, CFG, DB, JOURNAL, L1BlockInfo>
+impl<BLOCK, TX, CFG, DB, JOURNAL, F> ScrollBuilder
+    for Context<BLOCK, TX, CFG, DB, JOURNAL, ScrollChainContext<F>>
 where
     BLOCK: Block,
     TX: ScrollTxTr,
     CFG: Cfg<Spec = ScrollSpecId>,
     DB: Database,
     JOURNAL: JournalTr<Database = DB, State = EvmState>,
+    F: RollupFeeModel,
 {
     type Context = Self;
 
@@ -65,7 +70,7 @@ impl DefaultScrollContext for ScrollContext<EmptyDB> {
         Context::mainnet()
             .with_tx(ScrollTransaction::default())
             .with_cfg(cfg)
-            .with_chain(L1BlockInfo::default())
+            .with_chain(ScrollChainContext::default())
     }
 }
 
@@ -81,19 +86,26 @@ pub trait FeynmanEipActivations: EuclidEipActivations {
     fn maybe_with_eip_7623(self) -> Self;
 }
 
-impl<DB: Database> EuclidEipActivations for ScrollContext<DB> {
+impl<DB: Database, F> EuclidEipActivations for ScrollContext<DB, F> {
     fn maybe_with_eip_7702(mut self) -> Self {
         self.cfg.enable_eip7702 = self.cfg.spec >= ScrollSpecId::EUCLID;
         self
     }
 }
 
-impl<DB: Database> FeynmanEipActivations for ScrollContext<DB> {
+impl<DB: Database, F> FeynmanEipActivations for ScrollContext<DB, F> {
     fn maybe_with_eip_7623(mut self) -> Self {
         self.cfg.enable_eip7623 = self.cfg.spec >= ScrollSpecId::FEYNMAN;
         self
     }
 }
 
-pub type ScrollContext<DB> =
-    Context<BlockEnv, ScrollTransaction<TxEnv>, CfgEnv<ScrollSpecId>, DB, Journal<DB>, L1BlockInfo>;
+/// The Scroll [`Context`], using the rollup fee model `F`.
+pub type ScrollContext<DB, F = ScrollRollupFeeModel> = Context<
+    BlockEnv,
+    ScrollTransaction<TxEnv>,
+    CfgEnv<ScrollSpecId>,
+    DB,
+    Journal<DB>,
+    ScrollChainContext<F>,
+>;
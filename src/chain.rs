@@ -0,0 +1,343 @@
+//! Scroll chain context.
+
+use crate::{
+    alias::StrictL1Sender,
+    l1block::{L1BlockInfo, L1BlockInfoFinding, L1BlockInfoFindingAction, L1BlockInfoPolicy},
+    rollup_fee::{RollupFeeModel, ScrollRollupFeeModel},
+    ScrollSpecId,
+};
+use std::{vec, vec::Vec};
+
+use revm::{
+    primitives::{address, Address, U256},
+    state::{EvmStorage, EvmStorageSlot},
+};
+
+/// The address of the L2 transaction fee vault.
+pub const L2_TX_FEE_VAULT_ADDRESS: Address = address!("0x5300000000000000000000000000000000000005");
+
+/// The chain type of a Scroll EVM context.
+///
+/// The [`RollupFeeModel`] used by the handler is selected through this type.
+pub trait ScrollChainTr {
+    /// The model used to compute the rollup fee of transactions.
+    type FeeModel: RollupFeeModel;
+
+    /// Returns the Scroll chain context.
+    fn scroll_chain(&self) -> &ScrollChainContext<Self::FeeModel>;
+
+    /// Returns a mutable reference to the Scroll chain context.
+    fn scroll_chain_mut(&mut self) -> &mut ScrollChainContext<Self::FeeModel>;
+}
+
+/// The Scroll specific part of the EVM context.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct ScrollChainContext<F = ScrollRollupFeeModel> {
+    /// The model used to compute the rollup fee of transactions.
+    pub fee_model: F,
+    /// The recipients of the fees charged to transactions, None if all fees are credited to the
+    /// block beneficiary.
+    pub fee_routing: Option<ScrollFeeRouting>,
+    /// The L1 block information used to compute the rollup fee of transactions.
+    pub l1_block_info: L1BlockInfo,
+    /// The block number and spec the L1 block information was fetched for, None if it needs to be
+    /// fetched from the database for the next transaction.
+    ///
+    /// The cache is only valid for the database and the block it was fetched from. It is
+    /// invalidated when the [`ScrollEvm`](crate::ScrollEvm) is built or its block is set, and when
+    /// a transaction writes to the L1 gas price oracle: the journal is checked before each
+    /// transaction, and the state when it is finalized. Writes to the oracle outside of
+    /// transactions, e.g. through [`L1BlockInfo::write_to`] or directly to the database, must be
+    /// followed by [`ScrollChainContext::invalidate_l1_block_info`].
+    pub l1_block_info_fetched_at: Option<(U256, ScrollSpecId)>,
+    /// The actions taken for the findings of the [`L1BlockInfo::validate`] checks.
+    pub l1_block_info_policy: L1BlockInfoPolicy,
+    /// All the findings of the cached L1 block information, the policy is applied when they are
+    /// read through [`ScrollChainContext::l1_block_info_rejection`] and
+    /// [`ScrollChainContext::l1_block_info_warnings`].
+    pub l1_block_info_findings: Vec<L1BlockInfoFinding>,
+    /// The strict validation of L1 message senders, None if L1 message senders are only subject
+    /// to EIP-3607.
+    pub strict_l1_sender: Option<StrictL1Sender>,
+    /// The maximum payload size of transactions per spec, None if the payload size is not
+    /// checked, e.g. when replaying historical blocks.
+    pub max_tx_payload_size: Option<MaxTxPayloadSize>,
+    /// The fees charged for the last transaction, None if the transaction is an L1 message. This
+    /// is reset at the start of each transaction and only meaningful if the transaction
+    /// succeeded.
+    pub tx_fees: Option<ScrollTxFees>,
+}
+
+impl<F> ScrollChainContext<F> {
+    /// Returns a new [`ScrollChainContext`] using the provided rollup fee model.
+    pub fn with_fee_model(fee_model: F) -> Self {
+        Self {
+            fee_model,
+            fee_routing: None,
+            l1_block_info: L1BlockInfo::default(),
+            l1_block_info_fetched_at: None,
+            l1_block_info_policy: L1BlockInfoPolicy::default(),
+            l1_block_info_findings: Vec::new(),
+            strict_l1_sender: None,
+            max_tx_payload_size: None,
+            tx_fees: None,
+        }
+    }
+
+    /// Sets the actions taken for the findings of the [`L1BlockInfo::validate`] checks.
+    pub fn with_l1_block_info_policy(mut self, policy: L1BlockInfoPolicy) -> Self {
+        self.l1_block_info_policy = policy;
+        self
+    }
+
+    /// Enables the strict validation of L1 message senders.
+    pub fn with_strict_l1_sender(mut self, strict_l1_sender: StrictL1Sender) -> Self {
+        self.strict_l1_sender = Some(strict_l1_sender);
+        self
+    }
+
+    /// Enables the check of the payload size of transactions against the maximum payload size per
+    /// spec, e.g. [`MaxTxPayloadSize::uniform`] with [`SEQUENCER_MAX_TX_PAYLOAD_SIZE`] when
+    /// building blocks.
+    pub fn with_max_tx_payload_size(mut self, max_tx_payload_size: MaxTxPayloadSize) -> Self {
+        self.max_tx_payload_size = Some(max_tx_payload_size);
+        self
+    }
+
+    /// Caches the L1 block information fetched for the provided block and spec, alongside all the
+    /// findings of its validation.
+    pub fn cache_l1_block_info(
+        &mut self,
+        l1_block_info: L1BlockInfo,
+        block_number: U256,
+        spec: ScrollSpecId,
+    ) {
+        self.l1_block_info_findings = l1_block_info.validate(spec);
+        self.l1_block_info = l1_block_info;
+        self.l1_block_info_fetched_at = Some((block_number, spec));
+    }
+
+    /// Returns the first finding of the cached L1 block information rejected by the policy.
+    pub fn l1_block_info_rejection(&self) -> Option<&L1BlockInfoFinding> {
+        self.l1_block_info_findings.iter().find(|finding| {
+            self.l1_block_info_policy.action(finding) == L1BlockInfoFindingAction::Reject
+        })
+    }
+
+    /// Returns the findings of the cached L1 block information the policy warns about.
+    pub fn l1_block_info_warnings(&self) -> impl Iterator<Item = &L1BlockInfoFinding> {
+        self.l1_block_info_findings.iter().filter(|finding| {
+            self.l1_block_info_policy.action(finding) == L1BlockInfoFindingAction::Warn
+        })
+    }
+
+    /// Sets the recipients of the fees charged to transactions.
+    pub fn with_fee_routing(mut self, fee_routing: ScrollFeeRouting) -> Self {
+        self.fee_routing = Some(fee_routing);
+        self
+    }
+
+    /// Returns true if the cached L1 block information can be used for a transaction in the
+    /// provided block and spec.
+    pub fn is_l1_block_info_cached(&self, block_number: U256, spec: ScrollSpecId) -> bool {
+        self.l1_block_info_fetched_at == Some((block_number, spec))
+    }
+
+    /// Invalidates the cached L1 block information such that it is fetched from the database for
+    /// the next transaction.
+    pub fn invalidate_l1_block_info(&mut self) {
+        self.l1_block_info_fetched_at = None;
+    }
+
+    /// Invalidates the cached L1 block information if the storage of the L1 gas price oracle,
+    /// journaled or finalized, was written to.
+    pub fn invalidate_l1_block_info_on_oracle_write(&mut self, oracle_storage: &EvmStorage) {
+        if oracle_storage.values().any(EvmStorageSlot::is_changed) {
+            self.invalidate_l1_block_info();
+        }
+    }
+}
+
+impl<F: RollupFeeModel> ScrollChainTr for ScrollChainContext<F> {
+    type FeeModel = F;
+
+    fn scroll_chain(&self) -> &ScrollChainContext<F> {
+        self
+    }
+
+    fn scroll_chain_mut(&mut self) -> &mut ScrollChainContext<F> {
+        self
+    }
+}
+
+/// The recipients of the fees charged to transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScrollFeeRouting {
+    /// The recipient of the rollup fee, e.g. the [`L2_TX_FEE_VAULT_ADDRESS`].
+    pub l1_fee_recipient: Address,
+    /// The recipient of the execution fee.
+    pub execution_fee_recipient: Address,
+}
+
+/// The maximum payload size of L2 transactions accepted by the sequencer, matching the 128 KiB
+/// limit of the l2geth transaction pool. Historical blocks may contain larger transactions, so the
+/// limit is not enforced unless set through [`ScrollChainContext::with_max_tx_payload_size`].
+pub const SEQUENCER_MAX_TX_PAYLOAD_SIZE: usize = 128 * 1024;
+
+/// The default maximum gas limit of L1 messages, matching the `maxGasLimit` of the L1 message
+/// queue on mainnet.
+pub const DEFAULT_MAX_L1_MESSAGE_GAS_LIMIT: u64 = 10_000_000;
+
+/// The intrinsic gas of an L1 message charged by the L1 message queue.
+pub const L1_MESSAGE_INTRINSIC_GAS: u64 = 21_000;
+
+/// The gas per calldata byte of an L1 message charged by the L1 message queue.
+pub const L1_MESSAGE_CALLDATA_GAS: u64 = 16;
+
+/// The maximum payload size of transactions, such that transactions fit in the data availability
+/// chunks and circuits.
+///
+/// The payload size of an L2 transaction is the length of its RLP bytes, or of its input if the
+/// RLP bytes are not provided. L1 messages are checked against the limits of the L1 message queue
+/// instead: the gas limit must not exceed the maximum gas limit, and must cover the intrinsic gas
+/// of the calldata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MaxTxPayloadSize {
+    /// The maximum payload size of L2 transactions from each spec, in any order. Transactions are
+    /// not limited in the specs before the first activation.
+    pub limits: Vec<(ScrollSpecId, usize)>,
+    /// The maximum gas limit of L1 messages.
+    pub l1_message_max_gas_limit: u64,
+}
+
+impl Default for MaxTxPayloadSize {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl MaxTxPayloadSize {
+    /// Returns a new [`MaxTxPayloadSize`] with the provided limits per spec.
+    pub fn new(limits: Vec<(ScrollSpecId, usize)>) -> Self {
+        Self { limits, l1_message_max_gas_limit: DEFAULT_MAX_L1_MESSAGE_GAS_LIMIT }
+    }
+
+    /// Returns a new [`MaxTxPayloadSize`] with the same limit in all specs.
+    pub fn uniform(max_size: usize) -> Self {
+        Self::new(vec![(ScrollSpecId::SHANGHAI, max_size)])
+    }
+
+    /// Sets the maximum gas limit of L1 messages.
+    pub fn with_l1_message_max_gas_limit(mut self, l1_message_max_gas_limit: u64) -> Self {
+        self.l1_message_max_gas_limit = l1_message_max_gas_limit;
+        self
+    }
+
+    /// Returns the maximum payload size of L2 transactions in the spec, i.e. the limit with the
+    /// latest activation enabled in the spec, None if transactions are not limited.
+    pub fn for_spec(&self, spec: ScrollSpecId) -> Option<usize> {
+        self.limits
+            .iter()
+            .filter(|(activation, _)| spec.is_enabled_in(*activation))
+            .max_by_key(|(activation, _)| *activation)
+            .map(|(_, max_size)| *max_size)
+    }
+
+    /// Returns the maximum calldata size of an L1 message with the gas limit, such that the L1
+    /// message queue accepts it.
+    pub fn l1_message_max_size(gas_limit: u64) -> usize {
+        (gas_limit.saturating_sub(L1_MESSAGE_INTRINSIC_GAS) / L1_MESSAGE_CALLDATA_GAS) as usize
+    }
+}
+
+/// The fees charged for a transaction.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct ScrollTxFees {
+    /// The rollup fee charged for posting the transaction on L1.
+    pub l1_fee: U256,
+    /// The L1 block information used to compute the rollup fee.
+    pub l1_block_info: L1BlockInfo,
+    /// The execution fee charged for the gas used by the transaction.
+    pub execution_fee: U256,
+    /// The amount refunded to the caller for the unused gas, after applying the EIP-7623 floor.
+    pub refund: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::l1block::L1BlockInfoField;
+
+    #[test]
+    fn test_l1_block_info_findings_policy() {
+        let overflow = L1BlockInfoFinding::Overflow {
+            lhs: L1BlockInfoField::L1CommitScalar,
+            rhs: L1BlockInfoField::L1BaseFee,
+        };
+        let l1_block_info = L1BlockInfo {
+            l1_base_fee: U256::MAX,
+            l1_blob_base_fee: Some(U256::ONE),
+            l1_commit_scalar: Some(U256::from(2)),
+            l1_blob_scalar: Some(U256::ONE),
+            calldata_gas: Some(U256::ONE),
+            ..Default::default()
+        };
+        let mut chain = ScrollChainContext::with_fee_model(ScrollRollupFeeModel);
+
+        // all the findings are cached, even if the policy ignores them.
+        chain.cache_l1_block_info(l1_block_info, U256::from(1), ScrollSpecId::CURIE);
+        assert_eq!(chain.l1_block_info_findings, [overflow.clone()]);
+        assert_eq!(chain.l1_block_info_rejection(), None);
+        assert_eq!(chain.l1_block_info_warnings().count(), 0);
+
+        // the policy is applied when the findings are read.
+        chain.l1_block_info_policy =
+            L1BlockInfoPolicy { overflow: L1BlockInfoFindingAction::Warn, ..Default::default() };
+        assert_eq!(chain.l1_block_info_rejection(), None);
+        assert_eq!(chain.l1_block_info_warnings().collect::<Vec<_>>(), [&overflow]);
+
+        chain.l1_block_info_policy = L1BlockInfoPolicy::uniform(L1BlockInfoFindingAction::Reject);
+        assert_eq!(chain.l1_block_info_rejection(), Some(&overflow));
+        assert_eq!(chain.l1_block_info_warnings().count(), 0);
+    }
+
+    #[test]
+    fn test_max_tx_payload_size() {
+        let max_size = MaxTxPayloadSize::new(vec![
+            (ScrollSpecId::CURIE, 128 * 1024),
+            (ScrollSpecId::FEYNMAN, 64 * 1024),
+        ]);
+
+        assert_eq!(max_size.for_spec(ScrollSpecId::BERNOULLI), None);
+        assert_eq!(max_size.for_spec(ScrollSpecId::CURIE), Some(128 * 1024));
+        assert_eq!(max_size.for_spec(ScrollSpecId::EUCLID), Some(128 * 1024));
+        assert_eq!(max_size.for_spec(ScrollSpecId::GALILEO), Some(64 * 1024));
+
+        assert_eq!(MaxTxPayloadSize::default().for_spec(ScrollSpecId::GALILEO), None);
+        assert_eq!(MaxTxPayloadSize::uniform(10).for_spec(ScrollSpecId::SHANGHAI), Some(10));
+    }
+
+    #[test]
+    fn test_max_tx_payload_size_unsorted() {
+        let max_size = MaxTxPayloadSize::new(vec![
+            (ScrollSpecId::FEYNMAN, 64 * 1024),
+            (ScrollSpecId::CURIE, 128 * 1024),
+        ]);
+
+        assert_eq!(max_size.for_spec(ScrollSpecId::BERNOULLI), None);
+        assert_eq!(max_size.for_spec(ScrollSpecId::EUCLID), Some(128 * 1024));
+        assert_eq!(max_size.for_spec(ScrollSpecId::FEYNMAN), Some(64 * 1024));
+        assert_eq!(max_size.for_spec(ScrollSpecId::GALILEO), Some(64 * 1024));
+    }
+
+    #[test]
+    fn test_l1_message_max_size() {
+        assert_eq!(MaxTxPayloadSize::l1_message_max_size(0), 0);
+        assert_eq!(MaxTxPayloadSize::l1_message_max_size(21_000), 0);
+        assert_eq!(MaxTxPayloadSize::l1_message_max_size(21_031), 1);
+        assert_eq!(MaxTxPayloadSize::l1_message_max_size(21_032), 2);
+    }
+}
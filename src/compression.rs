@@ -0,0 +1,136 @@
+//! Compression of transactions following Scroll's data availability codec.
+//!
+//! The rollup fee of a transaction depends on the size of the transaction once compressed in the
+//! batch blob. Post-FEYNMAN the fee uses the compression ratio of the transaction and
+//! post-GALILEO its compressed size, both computed with the zstd configuration of the da-codec.
+
+use crate::{l1block::TX_L1_FEE_PRECISION_U256, ScrollSpecId, ScrollTransaction};
+use std::{io::Write, vec::Vec};
+
+use revm::{
+    context::Transaction,
+    primitives::{Bytes, U256},
+};
+use zstd::{
+    stream::Encoder,
+    zstd_safe::{CParameter, ParamSwitch},
+};
+
+/// The target size of a zstd block used by the da-codec.
+pub const N_BLOCK_SIZE_TARGET: u32 = 124 * 1024;
+
+/// The window log used by the da-codec.
+const WINDOW_LOG: u32 = 17;
+
+/// Compresses the data using the zstd configuration of the da-codec.
+///
+/// The frame is produced without magic bytes, checksum nor dictionary id, with the literal
+/// compression disabled and the content size included. The size of the data is pledged up front
+/// as done by the da-codec, which determines the frame header and the split of the blocks.
+pub fn compress_scroll_batch_bytes(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = Encoder::new(Vec::new(), 0)?;
+    encoder.set_parameter(CParameter::LiteralCompressionMode(ParamSwitch::Disable))?;
+    encoder.set_parameter(CParameter::WindowLog(WINDOW_LOG))?;
+    encoder.set_parameter(CParameter::TargetCBlockSize(N_BLOCK_SIZE_TARGET))?;
+    encoder.include_checksum(false)?;
+    encoder.include_magicbytes(false)?;
+    encoder.include_dictid(false)?;
+    encoder.include_contentsize(true)?;
+    encoder.set_pledged_src_size(Some(data.len() as u64))?;
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Returns the compressed size of the data.
+///
+/// Note: compressed_size(tx) = min(size(zstd(rlp(tx))), size(rlp(tx)))
+pub fn compressed_size(data: &[u8]) -> usize {
+    // compressing into an in-memory buffer with a valid configuration cannot fail.
+    let compressed =
+        compress_scroll_batch_bytes(data).expect("in-memory zstd compression is infallible");
+    compressed.len().min(data.len())
+}
+
+/// Returns the compression ratio of the data, scaled by [`TX_L1_FEE_PRECISION_U256`].
+///
+/// The compression ratio of empty data is infinite, in which case `U256::MAX` is returned.
+///
+/// Note: compression_ratio(tx) = size(tx) * 1e9 / size(zstd(tx))
+pub fn compression_ratio(data: &[u8]) -> U256 {
+    let compressed_size = compressed_size(data);
+    if compressed_size == 0 {
+        return U256::MAX;
+    }
+    U256::from(data.len())
+        .saturating_mul(TX_L1_FEE_PRECISION_U256)
+        .wrapping_div(U256::from(compressed_size))
+}
+
+impl<T: Transaction> ScrollTransaction<T> {
+    /// Returns a new [`ScrollTransaction`], computing the compression information required by the
+    /// spec from the RLP encoded transaction bytes.
+    ///
+    /// Pre-FEYNMAN no compression information is required, FEYNMAN requires the compression ratio
+    /// and post-GALILEO the compressed size.
+    pub fn with_computed_compression(base: T, rlp_bytes: Bytes, spec: ScrollSpecId) -> Self {
+        let (compression_ratio, compressed_size) = if spec.is_enabled_in(ScrollSpecId::GALILEO) {
+            (None, Some(compressed_size(&rlp_bytes)))
+        } else if spec.is_enabled_in(ScrollSpecId::FEYNMAN) {
+            (Some(compression_ratio(&rlp_bytes)), None)
+        } else {
+            (None, None)
+        };
+        Self::new(base, Some(rlp_bytes), compression_ratio, compressed_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        context::TxEnv,
+        primitives::{bytes, uint},
+    };
+    use rstest::rstest;
+
+    /// A signed EIP-1559 ERC-20 transfer.
+    const ERC20_TRANSFER: Bytes = bytes!("02f8b18308275005830f42408407270e0082fde89406efdbff2a14a7c8e15944d1f4a48f9f95f663a480b844a9059cbb000000000000000000000000665d0698dbc8fb95afc25c3a4d9cf280d87a585b00000000000000000000000000000000000000000000000000000000499602d2c001a082f3e9c695dc6b8d1b11818d5701919e286de8d47f7c3eb3100c485f79e57828a0e8bc163c82eee18733288c7d4ac636db3a6deb013ef2d37b68322be20edc45cc");
+
+    /// A signed EIP-1559 router call with ABI encoded calldata.
+    const ROUTER_CALL: Bytes = bytes!("02f9037b830827504d830f42408407270e00830557309480e38291e06339d10aab483c65695d004dbd5c6988016345785d8a0000b903045ae401dc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000007000000000000000000000000000000000000000000000000000000000000000e0000000000000000000000000000000000000000000000000000000000000015000000000000000000000000000000000000000000000000000000000000001c0000000000000000000000000000000000000000000000000000000000000023000000000000000000000000000000000000000000000000000000000000002a00000000000000000000000000000000000000000000000000000000000000310000000000000000000000000000000000000000000000000000000000000038000000000000000000000000000000000000000000000000000000000000003f0000000000000000000000000000000000000000000000000000000000000046000000000000000000000000000000000000000000000000000000000000004d0000000000000000000000000000000000000000000000000000000000000054000000000000000000000000000000000000000000000000000000000000005b0000000000000000000000000000000000000000000000000000000000000062000000000000000000000000000000000000000000000000000000000000006900000000000000000000000000000000000000000000000000000000000000700000000000000000000000000000000000000000000000000000000000000077000000000000000000000000000000000000000000000000000000000000007e0000000000000000000000000000000000000000000000000000000000000085000000000000000000000000000000000000000000000000000000000000008c0000000000000000000000000000000000000000000000000000000000000093000000000000000000000000000000000000000000000000000000000000009a00000000000000000000000000000000000000000000000000000000000000a1c080a0db77fd01af957221a4989b64b3770a83a3c56068405b9f0e9408feae57fd17e4a0ad328846aa18b32a335816374511cac1063c704b8c57999e51da9f908290a7a4");
+
+    #[rstest]
+    #[case(ERC20_TRANSFER, 156, uint!(1153846153_U256))]
+    #[case(ROUTER_CALL, 217, uint!(4124423963_U256))]
+    #[case(Bytes::from_static(&[0u8; 1000]), 15, uint!(66666666666_U256))]
+    fn test_compression(
+        #[case] rlp_bytes: Bytes,
+        #[case] expected_size: usize,
+        #[case] expected_ratio: U256,
+    ) {
+        assert_eq!(compressed_size(&rlp_bytes), expected_size);
+        assert_eq!(compression_ratio(&rlp_bytes), expected_ratio);
+    }
+
+    #[test]
+    fn test_compression_empty_data() {
+        assert_eq!(compressed_size(&[]), 0);
+        assert_eq!(compression_ratio(&[]), U256::MAX);
+    }
+
+    #[rstest]
+    #[case(ScrollSpecId::EUCLID, None, None)]
+    #[case(ScrollSpecId::FEYNMAN, Some(uint!(1153846153_U256)), None)]
+    #[case(ScrollSpecId::GALILEO, None, Some(156))]
+    fn test_with_computed_compression(
+        #[case] spec: ScrollSpecId,
+        #[case] expected_ratio: Option<U256>,
+        #[case] expected_size: Option<usize>,
+    ) {
+        let tx =
+            ScrollTransaction::with_computed_compression(TxEnv::default(), ERC20_TRANSFER, spec);
+        assert_eq!(tx.rlp_bytes, Some(ERC20_TRANSFER));
+        assert_eq!(tx.compression_ratio, expected_ratio);
+        assert_eq!(tx.compressed_size, expected_size);
+    }
+}
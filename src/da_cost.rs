@@ -0,0 +1,229 @@
+//! Batch data availability cost reconciliation.
+//!
+//! The rollup fee charged to each transaction is an estimate of its share of the L1 cost of the
+//! batch it is included in. This module compares the rollup fees collected for the transactions
+//! of a batch to the actual cost of committing the batch on L1.
+//!
+//! The actual cost is expressed with the scalar semantics of the [`L1BlockInfo`]: the break-even
+//! exec and blob scalars are the scalars for which the post-GALILEO fee per compressed byte,
+//! `exec_scalar * l1_base_fee + blob_scalar * l1_blob_base_fee`, exactly covers the cost of the
+//! batch. Scalars are scaled by [`TX_L1_FEE_PRECISION`](crate::l1block::TX_L1_FEE_PRECISION).
+
+use crate::{
+    l1block::{L1BlockInfo, L1FeeError, TX_L1_FEE_PRECISION_U256},
+    rollup_fee::{tx_rollup_fee, RollupFeeModel},
+    transaction::ScrollTxTr,
+    ScrollSpecId,
+};
+use core::cmp::Ordering;
+
+use revm::primitives::{eip4844::GAS_PER_BLOB, U256};
+
+/// The number of bytes of batch data held by a blob: 4096 field elements of 31 usable bytes.
+pub const MAX_BLOB_DATA_SIZE: usize = 4096 * 31;
+
+/// The rollup fees collected for the transactions of a batch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchFees {
+    /// The number of transactions in the batch.
+    pub tx_count: usize,
+    /// The total size of the RLP encoded transactions of the batch.
+    pub tx_size: usize,
+    /// The total rollup fee collected for the transactions of the batch.
+    pub collected: U256,
+}
+
+impl BatchFees {
+    /// Adds a transaction to the batch, computing its rollup fee with the fee model from the
+    /// [`L1BlockInfo`] of its block. Returns the rollup fee of the transaction.
+    pub fn push_tx<F: RollupFeeModel, TX: ScrollTxTr>(
+        &mut self,
+        fee_model: &F,
+        l1_block_info: &L1BlockInfo,
+        tx: &TX,
+        spec_id: ScrollSpecId,
+    ) -> Result<U256, L1FeeError> {
+        let l1_fee = tx_rollup_fee(fee_model, l1_block_info, tx, spec_id)?;
+        self.push_fee(tx.rlp_bytes().map_or(0, |rlp_bytes| rlp_bytes.len()), l1_fee);
+        Ok(l1_fee)
+    }
+
+    /// Adds a transaction with a known rollup fee to the batch, e.g. the fee recorded in its
+    /// receipt.
+    pub fn push_fee(&mut self, tx_size: usize, l1_fee: U256) {
+        self.tx_count += 1;
+        self.tx_size += tx_size;
+        self.collected = self.collected.saturating_add(l1_fee);
+    }
+}
+
+/// The actual L1 cost of committing a batch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BatchDaCost {
+    /// The L1 base fee paid by the commit transaction.
+    pub l1_base_fee: U256,
+    /// The L1 blob base fee paid by the commit transaction.
+    pub l1_blob_base_fee: U256,
+    /// The L1 execution gas attributed to the batch, e.g. the commit and verification gas.
+    pub l1_gas_used: u64,
+    /// The compressed size of the batch data.
+    pub compressed_size: usize,
+}
+
+impl BatchDaCost {
+    /// Returns the number of blobs holding the batch data.
+    pub fn blob_count(&self) -> u64 {
+        self.compressed_size.div_ceil(MAX_BLOB_DATA_SIZE) as u64
+    }
+
+    /// Returns the execution cost of the batch, `l1_gas_used * l1_base_fee`.
+    pub fn exec_cost(&self) -> U256 {
+        U256::from(self.l1_gas_used).saturating_mul(self.l1_base_fee)
+    }
+
+    /// Returns the data availability cost of the batch, `blob_gas * l1_blob_base_fee`.
+    pub fn blob_cost(&self) -> U256 {
+        U256::from(self.blob_count() * GAS_PER_BLOB).saturating_mul(self.l1_blob_base_fee)
+    }
+
+    /// Returns the total L1 cost of the batch.
+    pub fn total(&self) -> U256 {
+        self.exec_cost().saturating_add(self.blob_cost())
+    }
+
+    /// Returns the exec scalar for which the fee per compressed byte covers the execution cost of
+    /// the batch, None if the batch is empty.
+    pub fn break_even_exec_scalar(&self) -> Option<U256> {
+        self.per_compressed_byte(U256::from(self.l1_gas_used))
+    }
+
+    /// Returns the blob scalar for which the fee per compressed byte covers the data availability
+    /// cost of the batch, None if the batch is empty.
+    pub fn break_even_blob_scalar(&self) -> Option<U256> {
+        self.per_compressed_byte(U256::from(self.blob_count() * GAS_PER_BLOB))
+    }
+
+    /// Returns the gas per compressed byte of the batch, scaled like the scalars.
+    fn per_compressed_byte(&self, gas: U256) -> Option<U256> {
+        gas.saturating_mul(TX_L1_FEE_PRECISION_U256).checked_div(U256::from(self.compressed_size))
+    }
+}
+
+/// The difference between the rollup fees collected for a batch and its actual cost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FeeCollection {
+    /// The collected fees exactly cover the cost of the batch.
+    Exact,
+    /// The collected fees exceed the cost of the batch by the amount.
+    Over(U256),
+    /// The collected fees fall short of the cost of the batch by the amount.
+    Under(U256),
+}
+
+/// The reconciliation of the rollup fees collected for a batch with its actual cost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchReconciliation {
+    /// The rollup fees collected for the batch.
+    pub fees: BatchFees,
+    /// The actual cost of the batch.
+    pub cost: BatchDaCost,
+    /// The over or under collection of the batch.
+    pub collection: FeeCollection,
+    /// The ratio of the collected fees to the cost of the batch, scaled by
+    /// [`TX_L1_FEE_PRECISION`](crate::l1block::TX_L1_FEE_PRECISION), None if the batch has no cost.
+    pub collection_ratio: Option<U256>,
+}
+
+/// Reconciles the rollup fees collected for a batch with its actual L1 cost.
+pub fn reconcile_batch(fees: BatchFees, cost: BatchDaCost) -> BatchReconciliation {
+    let total = cost.total();
+    let collection = match fees.collected.cmp(&total) {
+        Ordering::Equal => FeeCollection::Exact,
+        Ordering::Greater => FeeCollection::Over(fees.collected - total),
+        Ordering::Less => FeeCollection::Under(total - fees.collected),
+    };
+    let collection_ratio =
+        fees.collected.saturating_mul(TX_L1_FEE_PRECISION_U256).checked_div(total);
+    BatchReconciliation { fees, cost, collection, collection_ratio }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rollup_fee::ScrollRollupFeeModel,
+        test_utils::{context, l1_block_info, L1_DATA_COST},
+    };
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    #[case(MAX_BLOB_DATA_SIZE, 1)]
+    #[case(MAX_BLOB_DATA_SIZE + 1, 2)]
+    #[case(6 * MAX_BLOB_DATA_SIZE, 6)]
+    fn test_blob_count(#[case] compressed_size: usize, #[case] expected: u64) {
+        let cost = BatchDaCost { compressed_size, ..Default::default() };
+        assert_eq!(cost.blob_count(), expected);
+    }
+
+    #[test]
+    fn test_reconcile_batch_under_collection() -> Result<(), L1FeeError> {
+        let tx = context().tx;
+        let mut fees = BatchFees::default();
+        for _ in 0..2 {
+            let l1_fee =
+                fees.push_tx(&ScrollRollupFeeModel, &l1_block_info(), &tx, ScrollSpecId::CURIE)?;
+            assert_eq!(l1_fee, L1_DATA_COST);
+        }
+        assert_eq!(
+            fees,
+            BatchFees { tx_count: 2, tx_size: 8, collected: L1_DATA_COST * U256::from(2) }
+        );
+
+        let cost = BatchDaCost {
+            l1_base_fee: U256::from(1),
+            l1_blob_base_fee: U256::from(1),
+            l1_gas_used: 50_000,
+            compressed_size: 100,
+        };
+        assert_eq!(cost.exec_cost(), U256::from(50_000));
+        assert_eq!(cost.blob_cost(), U256::from(GAS_PER_BLOB));
+        assert_eq!(cost.break_even_exec_scalar(), Some(U256::from(500_000_000_000u64)));
+        assert_eq!(cost.break_even_blob_scalar(), Some(U256::from(1_310_720_000_000u64)));
+
+        let reconciliation = reconcile_batch(fees, cost);
+        assert_eq!(reconciliation.collection, FeeCollection::Under(U256::from(101_072)));
+        assert_eq!(reconciliation.collection_ratio, Some(U256::from(441_813_201)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_batch_over_collection() {
+        let cost = BatchDaCost {
+            l1_base_fee: U256::from(10),
+            l1_blob_base_fee: U256::from(1),
+            l1_gas_used: 100_000,
+            compressed_size: MAX_BLOB_DATA_SIZE,
+        };
+        let total = cost.total();
+        assert_eq!(total, U256::from(1_000_000 + GAS_PER_BLOB));
+
+        let mut fees = BatchFees::default();
+        fees.push_fee(1_000, total);
+        let reconciliation = reconcile_batch(fees.clone(), cost);
+        assert_eq!(reconciliation.collection, FeeCollection::Exact);
+        assert_eq!(reconciliation.collection_ratio, Some(TX_L1_FEE_PRECISION_U256));
+
+        fees.push_fee(1_000, U256::from(500));
+        let reconciliation = reconcile_batch(fees, cost);
+        assert_eq!(reconciliation.collection, FeeCollection::Over(U256::from(500)));
+
+        // an empty batch has no cost.
+        let reconciliation = reconcile_batch(BatchFees::default(), BatchDaCost::default());
+        assert_eq!(reconciliation.collection, FeeCollection::Exact);
+        assert_eq!(reconciliation.collection_ratio, None);
+        assert_eq!(BatchDaCost::default().break_even_exec_scalar(), None);
+    }
+}
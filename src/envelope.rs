@@ -0,0 +1,469 @@
+//! Signed Scroll transaction envelopes.
+//!
+//! Signed transactions are encoded as EIP-2718 envelopes, `tx_type || rlp(fields)`, or as the
+//! `rlp(fields)` of a legacy transaction. Scroll supports legacy, EIP-2930, EIP-1559, EIP-7702 and
+//! [`TxL1Message`] envelopes. The exact encoding of the envelope is used as the RLP bytes of the
+//! decoded [`ScrollTransaction`], from which the rollup fee is computed.
+
+use crate::transaction::{ScrollTransaction, TxL1Message, L1_MESSAGE_TYPE};
+use core::fmt;
+use std::vec::Vec;
+
+use alloy_rlp::{Decodable, Encodable, Header};
+use revm::{
+    context::{
+        either::Either,
+        transaction::{
+            AccessList, AccessListItem, Authorization, RecoveredAuthorization, SignedAuthorization,
+        },
+        TransactionType, TxEnv,
+    },
+    precompile::secp256k1,
+    primitives::{Address, Bytes, Keccak256, TxKind, B256, B512, U256},
+};
+
+/// The order of the secp256k1 curve.
+const SECP256K1N: U256 = U256::from_limbs([
+    0xbfd25e8cd0364141,
+    0xbaaedce6af48a03b,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+]);
+
+/// Half the order of the secp256k1 curve, the maximum `s` value of a signature (EIP-2).
+const SECP256K1N_HALF: U256 = U256::from_limbs([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// An error that occurred while decoding a signed transaction envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxEnvelopeError {
+    /// The envelope is not a valid RLP encoding of the transaction.
+    Rlp(alloy_rlp::Error),
+    /// The type of the envelope is not supported by Scroll.
+    UnsupportedTxType(u8),
+    /// The signature of the transaction is invalid or the sender could not be recovered.
+    InvalidSignature,
+}
+
+impl core::error::Error for TxEnvelopeError {}
+
+impl fmt::Display for TxEnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rlp(error) => write!(f, "invalid transaction envelope: {error}"),
+            Self::UnsupportedTxType(tx_type) => {
+                write!(f, "unsupported transaction envelope type {tx_type}")
+            }
+            Self::InvalidSignature => write!(f, "invalid transaction signature"),
+        }
+    }
+}
+
+impl From<alloy_rlp::Error> for TxEnvelopeError {
+    fn from(value: alloy_rlp::Error) -> Self {
+        Self::Rlp(value)
+    }
+}
+
+/// Decodes a signed transaction envelope into a [`ScrollTransaction`], recovering the sender of
+/// the transaction. The envelope must span the whole input.
+pub fn decode_signed_tx(raw: &[u8]) -> Result<ScrollTransaction<TxEnv>, TxEnvelopeError> {
+    let Some((&first, payload)) = raw.split_first() else {
+        return Err(alloy_rlp::Error::InputTooShort.into());
+    };
+
+    let base = match first {
+        // legacy transactions are encoded as an RLP list.
+        0xc0..=0xff => decode_legacy(raw)?,
+        0x80..=0xbf => return Err(alloy_rlp::Error::UnexpectedString.into()),
+        L1_MESSAGE_TYPE => {
+            let mut buf = raw;
+            let tx = TxL1Message::decode_2718(&mut buf)?;
+            if !buf.is_empty() {
+                return Err(alloy_rlp::Error::UnexpectedLength.into());
+            }
+            return Ok(tx.into());
+        }
+        tx_type => match TransactionType::from(tx_type) {
+            TransactionType::Eip2930 => decode_eip2930(payload)?,
+            TransactionType::Eip1559 => decode_eip1559(payload)?,
+            TransactionType::Eip7702 => decode_eip7702(payload)?,
+            _ => return Err(TxEnvelopeError::UnsupportedTxType(tx_type)),
+        },
+    };
+
+    Ok(ScrollTransaction::new(base, Some(Bytes::copy_from_slice(raw)), None, None))
+}
+
+/// Decodes a legacy transaction, `rlp([nonce, gas_price, gas_limit, to, value, data, v, r, s])`.
+fn decode_legacy(raw: &[u8]) -> Result<TxEnv, TxEnvelopeError> {
+    let fields = list_payload(raw)?;
+    let mut buf = fields;
+
+    let nonce = u64::decode(&mut buf)?;
+    let gas_price = u128::decode(&mut buf)?;
+    let gas_limit = u64::decode(&mut buf)?;
+    let kind = TxKind::decode(&mut buf)?;
+    let value = U256::decode(&mut buf)?;
+    let data = Bytes::decode(&mut buf)?;
+    let unsigned = &fields[..fields.len() - buf.len()];
+
+    // EIP-155: `v = chain_id * 2 + 35 + y_parity` for replay protected transactions.
+    let v = u64::decode(&mut buf)?;
+    let (chain_id, y_parity) = match v {
+        27 | 28 => (None, v == 28),
+        35.. => (Some((v - 35) / 2), (v - 35) % 2 == 1),
+        _ => return Err(TxEnvelopeError::InvalidSignature),
+    };
+    let signature = Signature { y_parity, r: U256::decode(&mut buf)?, s: U256::decode(&mut buf)? };
+    ensure_consumed(buf)?;
+
+    // replay protected transactions sign over `[.., chain_id, 0, 0]`.
+    let mut suffix = Vec::new();
+    if let Some(chain_id) = chain_id {
+        chain_id.encode(&mut suffix);
+        suffix.extend_from_slice(&[alloy_rlp::EMPTY_STRING_CODE; 2]);
+    }
+    let caller = signature.recover(signing_hash(None, unsigned, &suffix))?;
+
+    Ok(TxEnv {
+        tx_type: TransactionType::Legacy as u8,
+        caller,
+        gas_limit,
+        gas_price,
+        kind,
+        value,
+        data,
+        nonce,
+        chain_id,
+        ..Default::default()
+    })
+}
+
+/// Decodes an EIP-2930 transaction,
+/// `rlp([chain_id, nonce, gas_price, gas_limit, to, value, data, access_list, y_parity, r, s])`.
+fn decode_eip2930(payload: &[u8]) -> Result<TxEnv, TxEnvelopeError> {
+    let fields = list_payload(payload)?;
+    let mut buf = fields;
+
+    let chain_id = u64::decode(&mut buf)?;
+    let nonce = u64::decode(&mut buf)?;
+    let gas_price = u128::decode(&mut buf)?;
+    let gas_limit = u64::decode(&mut buf)?;
+    let kind = TxKind::decode(&mut buf)?;
+    let value = U256::decode(&mut buf)?;
+    let data = Bytes::decode(&mut buf)?;
+    let access_list = decode_access_list(&mut buf)?;
+    let unsigned = &fields[..fields.len() - buf.len()];
+
+    let signature = Signature::decode(&mut buf)?;
+    ensure_consumed(buf)?;
+    let tx_type = TransactionType::Eip2930 as u8;
+    let caller = signature.recover(signing_hash(Some(tx_type), unsigned, &[]))?;
+
+    Ok(TxEnv {
+        tx_type,
+        caller,
+        gas_limit,
+        gas_price,
+        kind,
+        value,
+        data,
+        nonce,
+        chain_id: Some(chain_id),
+        access_list,
+        ..Default::default()
+    })
+}
+
+/// Decodes an EIP-1559 transaction, `rlp([chain_id, nonce, max_priority_fee_per_gas,
+/// max_fee_per_gas, gas_limit, to, value, data, access_list, y_parity, r, s])`.
+fn decode_eip1559(payload: &[u8]) -> Result<TxEnv, TxEnvelopeError> {
+    let fields = list_payload(payload)?;
+    let mut buf = fields;
+
+    let chain_id = u64::decode(&mut buf)?;
+    let nonce = u64::decode(&mut buf)?;
+    let max_priority_fee_per_gas = u128::decode(&mut buf)?;
+    let max_fee_per_gas = u128::decode(&mut buf)?;
+    let gas_limit = u64::decode(&mut buf)?;
+    let kind = TxKind::decode(&mut buf)?;
+    let value = U256::decode(&mut buf)?;
+    let data = Bytes::decode(&mut buf)?;
+    let access_list = decode_access_list(&mut buf)?;
+    let unsigned = &fields[..fields.len() - buf.len()];
+
+    let signature = Signature::decode(&mut buf)?;
+    ensure_consumed(buf)?;
+    let tx_type = TransactionType::Eip1559 as u8;
+    let caller = signature.recover(signing_hash(Some(tx_type), unsigned, &[]))?;
+
+    Ok(TxEnv {
+        tx_type,
+        caller,
+        gas_limit,
+        gas_price: max_fee_per_gas,
+        kind,
+        value,
+        data,
+        nonce,
+        chain_id: Some(chain_id),
+        access_list,
+        gas_priority_fee: Some(max_priority_fee_per_gas),
+        ..Default::default()
+    })
+}
+
+/// Decodes an EIP-7702 transaction, `rlp([chain_id, nonce, max_priority_fee_per_gas,
+/// max_fee_per_gas, gas_limit, destination, value, data, access_list, authorization_list,
+/// y_parity, r, s])`. EIP-7702 transactions cannot create contracts.
+fn decode_eip7702(payload: &[u8]) -> Result<TxEnv, TxEnvelopeError> {
+    let fields = list_payload(payload)?;
+    let mut buf = fields;
+
+    let chain_id = u64::decode(&mut buf)?;
+    let nonce = u64::decode(&mut buf)?;
+    let max_priority_fee_per_gas = u128::decode(&mut buf)?;
+    let max_fee_per_gas = u128::decode(&mut buf)?;
+    let gas_limit = u64::decode(&mut buf)?;
+    let to = Address::decode(&mut buf)?;
+    let value = U256::decode(&mut buf)?;
+    let data = Bytes::decode(&mut buf)?;
+    let access_list = decode_access_list(&mut buf)?;
+    let authorization_list = decode_authorization_list(&mut buf)?;
+    let unsigned = &fields[..fields.len() - buf.len()];
+
+    let signature = Signature::decode(&mut buf)?;
+    ensure_consumed(buf)?;
+    let tx_type = TransactionType::Eip7702 as u8;
+    let caller = signature.recover(signing_hash(Some(tx_type), unsigned, &[]))?;
+
+    Ok(TxEnv {
+        tx_type,
+        caller,
+        gas_limit,
+        gas_price: max_fee_per_gas,
+        kind: TxKind::Call(to),
+        value,
+        data,
+        nonce,
+        chain_id: Some(chain_id),
+        access_list,
+        gas_priority_fee: Some(max_priority_fee_per_gas),
+        authorization_list,
+        ..Default::default()
+    })
+}
+
+/// Decodes an access list, `rlp([[address, [storage_key, ..]], ..])`.
+fn decode_access_list(buf: &mut &[u8]) -> alloy_rlp::Result<AccessList> {
+    let mut list = Header::decode_bytes(buf, true)?;
+    let mut items = Vec::new();
+    while !list.is_empty() {
+        let mut item = Header::decode_bytes(&mut list, true)?;
+        let address = Address::decode(&mut item)?;
+        let storage_keys = Vec::<B256>::decode(&mut item)?;
+        ensure_consumed(item)?;
+        items.push(AccessListItem { address, storage_keys });
+    }
+    Ok(AccessList(items))
+}
+
+/// Decodes an authorization list, `rlp([[chain_id, address, nonce, y_parity, r, s], ..])`. The
+/// authorities are recovered during execution.
+fn decode_authorization_list(
+    buf: &mut &[u8],
+) -> alloy_rlp::Result<Vec<Either<SignedAuthorization, RecoveredAuthorization>>> {
+    let mut list = Header::decode_bytes(buf, true)?;
+    let mut authorizations = Vec::new();
+    while !list.is_empty() {
+        let mut item = Header::decode_bytes(&mut list, true)?;
+        let authorization = Authorization {
+            chain_id: U256::decode(&mut item)?,
+            address: Address::decode(&mut item)?,
+            nonce: u64::decode(&mut item)?,
+        };
+        let y_parity = u8::decode(&mut item)?;
+        let r = U256::decode(&mut item)?;
+        let s = U256::decode(&mut item)?;
+        ensure_consumed(item)?;
+        authorizations.push(Either::Left(SignedAuthorization::new_unchecked(
+            authorization,
+            y_parity,
+            r,
+            s,
+        )));
+    }
+    Ok(authorizations)
+}
+
+/// Returns the payload of the RLP list spanning the whole input.
+fn list_payload(raw: &[u8]) -> alloy_rlp::Result<&[u8]> {
+    let mut buf = raw;
+    let payload = Header::decode_bytes(&mut buf, true)?;
+    ensure_consumed(buf)?;
+    Ok(payload)
+}
+
+/// Returns an error if the input was not fully consumed.
+fn ensure_consumed(buf: &[u8]) -> alloy_rlp::Result<()> {
+    if !buf.is_empty() {
+        return Err(alloy_rlp::Error::UnexpectedLength);
+    }
+    Ok(())
+}
+
+/// Returns the hash signed by the sender, the keccak256 of `tx_type || rlp(unsigned || suffix)`
+/// where `unsigned` is the RLP encoding of the unsigned fields of the transaction.
+fn signing_hash(tx_type: Option<u8>, unsigned: &[u8], suffix: &[u8]) -> B256 {
+    let mut header = Vec::new();
+    Header { list: true, payload_length: unsigned.len() + suffix.len() }.encode(&mut header);
+
+    let mut hasher = Keccak256::new();
+    if let Some(tx_type) = tx_type {
+        hasher.update([tx_type]);
+    }
+    hasher.update(header);
+    hasher.update(unsigned);
+    hasher.update(suffix);
+    hasher.finalize()
+}
+
+/// The signature of a transaction.
+struct Signature {
+    y_parity: bool,
+    r: U256,
+    s: U256,
+}
+
+impl Signature {
+    /// Decodes the `[y_parity, r, s]` signature fields of a typed transaction.
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self { y_parity: bool::decode(buf)?, r: U256::decode(buf)?, s: U256::decode(buf)? })
+    }
+
+    /// Recovers the signer of the hash. Signatures with a high `s` value are rejected (EIP-2).
+    fn recover(&self, hash: B256) -> Result<Address, TxEnvelopeError> {
+        if self.r.is_zero() || self.r >= SECP256K1N || self.s.is_zero() || self.s > SECP256K1N_HALF
+        {
+            return Err(TxEnvelopeError::InvalidSignature);
+        }
+
+        let mut signature = B512::ZERO;
+        signature[..32].copy_from_slice(&self.r.to_be_bytes::<32>());
+        signature[32..].copy_from_slice(&self.s.to_be_bytes::<32>());
+        let signer = secp256k1::ecrecover(&signature, self.y_parity as u8, &hash)
+            .map_err(|_| TxEnvelopeError::InvalidSignature)?;
+        Ok(Address::from_slice(&signer[12..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::ScrollTxTr;
+    use revm::{
+        context::Transaction,
+        primitives::{address, hex},
+    };
+    use rstest::rstest;
+
+    /// The sender of the test transactions, signed with the private key `0x4646..46`.
+    const SENDER: Address = address!("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F");
+    const TO: Address = address!("0x00000000000000000000000000000000000dead1");
+
+    /// The EIP-155 example transaction.
+    const LEGACY: [u8; 110] = hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+    const EIP2930: [u8; 166] = hex!("01f8a38308275080843b9aca0082c3509400000000000000000000000000000000000dead101820102f838f79400000000000000000000000000000000000dead1e1a0000000000000000000000000000000000000000000000000000000000000000101a01f9aacd35c4cb22d5faa88c6e061bf05d29950a9cef11005d9a35a32353402a7a0159a0f207d0fee0498a1c4d60e528a8205c6ffba7322d1526a574a9a9d266a65");
+    const EIP1559: [u8; 169] = hex!("02f8a683082750018203e8847735940082c3509400000000000000000000000000000000000dead101820102f838f79400000000000000000000000000000000000dead1e1a0000000000000000000000000000000000000000000000000000000000000000101a024a7af021ac353c70aca473c720dcc6b09b6d884dcf6c488ed68c273817c8221a05bee1f092566229b75be327ccaa54e5554a4eaa59a9d46b62536159c4fe56b0c");
+    const EIP7702: [u8; 142] = hex!("04f88b83082750028203e88477359400830186a09400000000000000000000000000000000000dead18080c0dedd830827509400000000000000000000000000000000000000078001020380a01b0d9787bd877454245e18c60aa333e53b17a8de90ae4c9f72cb1e7f54ea24a3a04bc3932f029f8af1c22a0ec91ef9b5cad09cc36966bf905e05c60d7bc0f81fb0");
+    const L1_MESSAGE: [u8; 64] = hex!("7ef83d01830186a09400000000000000000000000000000000000dead1880de0b6b3a7640000840102030494000000000000000000000000000000000000dead");
+
+    #[rstest]
+    #[case(&LEGACY, TransactionType::Legacy as u8)]
+    #[case(&EIP2930, TransactionType::Eip2930 as u8)]
+    #[case(&EIP1559, TransactionType::Eip1559 as u8)]
+    #[case(&EIP7702, TransactionType::Eip7702 as u8)]
+    fn test_decode_signed_tx(
+        #[case] raw: &[u8],
+        #[case] tx_type: u8,
+    ) -> Result<(), TxEnvelopeError> {
+        let tx = decode_signed_tx(raw)?;
+
+        assert_eq!(tx.tx_type(), tx_type);
+        assert_eq!(tx.caller(), SENDER);
+        assert_eq!(tx.rlp_bytes(), Some(&Bytes::copy_from_slice(raw)));
+        assert!(!tx.is_l1_msg());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_signed_tx_fields() -> Result<(), TxEnvelopeError> {
+        let tx = decode_signed_tx(&LEGACY)?;
+        assert_eq!(tx.chain_id(), Some(1));
+        assert_eq!(tx.nonce(), 9);
+        assert_eq!(tx.gas_price(), 20_000_000_000);
+        assert_eq!(tx.gas_limit(), 21_000);
+        assert_eq!(tx.kind(), TxKind::Call(address!("0x3535353535353535353535353535353535353535")));
+        assert_eq!(tx.value(), U256::from(10u64.pow(18)));
+
+        let tx = decode_signed_tx(&EIP1559)?;
+        assert_eq!(tx.chain_id(), Some(534352));
+        assert_eq!(tx.nonce(), 1);
+        assert_eq!(tx.max_fee_per_gas(), 2_000_000_000);
+        assert_eq!(tx.max_priority_fee_per_gas(), Some(1_000));
+        assert_eq!(tx.kind(), TxKind::Call(TO));
+        assert_eq!(tx.input(), &Bytes::from_static(&[1, 2]));
+        assert_eq!(
+            tx.base.access_list,
+            AccessList(std::vec![AccessListItem {
+                address: TO,
+                storage_keys: std::vec![B256::with_last_byte(1)]
+            }])
+        );
+
+        let tx = decode_signed_tx(&EIP7702)?;
+        assert_eq!(tx.kind(), TxKind::Call(TO));
+        assert_eq!(tx.authorization_list_len(), 1);
+
+        let tx = decode_signed_tx(&L1_MESSAGE)?;
+        assert!(tx.is_l1_msg());
+        assert_eq!(ScrollTxTr::queue_index(&tx), Some(1));
+        assert_eq!(tx.rlp_bytes(), Some(&Bytes::copy_from_slice(&L1_MESSAGE)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_signed_tx_errors() {
+        assert_eq!(
+            decode_signed_tx(&[]),
+            Err(TxEnvelopeError::Rlp(alloy_rlp::Error::InputTooShort))
+        );
+
+        // blob and unknown transaction types are not supported.
+        assert_eq!(decode_signed_tx(&[0x03, 0xc0]), Err(TxEnvelopeError::UnsupportedTxType(0x03)));
+        assert_eq!(decode_signed_tx(&[0x05, 0xc0]), Err(TxEnvelopeError::UnsupportedTxType(0x05)));
+
+        // the envelope must span the whole input.
+        let mut trailing = EIP1559.to_vec();
+        trailing.push(0);
+        assert_eq!(
+            decode_signed_tx(&trailing),
+            Err(TxEnvelopeError::Rlp(alloy_rlp::Error::UnexpectedLength))
+        );
+        assert!(matches!(
+            decode_signed_tx(&EIP1559[..EIP1559.len() - 1]),
+            Err(TxEnvelopeError::Rlp(_))
+        ));
+
+        // the EIP-155 example with the high `s` value of the same signature.
+        let high_s = hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008026a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a098341627668089e51348fccfb4c7ff31c55912f2d2e47ef09652acf665fad3be");
+        assert_eq!(decode_signed_tx(&high_s), Err(TxEnvelopeError::InvalidSignature));
+    }
+}
@@ -0,0 +1,96 @@
+//! Scroll specific transaction errors.
+
+use crate::{
+    l1block::{L1BlockInfoFinding, L1FeeError},
+    transaction::SYSTEM_ADDRESS,
+    ScrollSpecId,
+};
+use core::fmt;
+
+use revm::{
+    context_interface::result::{EVMError, InvalidTransaction, TransactionError},
+    primitives::Address,
+};
+
+/// The Scroll transaction validation error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollInvalidTransaction {
+    /// An error from the base Ethereum transaction validation.
+    Base(InvalidTransaction),
+    /// The rollup fee of the transaction could not be computed.
+    L1Fee(L1FeeError),
+    /// The L1 block information violates the policy of the chain.
+    InvalidL1BlockInfo(L1BlockInfoFinding),
+    /// The sender of the L1 message is a known L1 contract whose address was not aliased.
+    UnaliasedL1Sender { sender: Address },
+    /// The sender of the L1 message has code deployed on L2.
+    L1SenderWithCode { sender: Address },
+    /// The transaction type is not supported by the spec.
+    UnsupportedTxType { tx_type: u8, spec_id: ScrollSpecId },
+    /// The transaction sets blob fields, which are not supported by Scroll.
+    BlobFieldsNotSupported,
+    /// The caller of the transaction is the system address, but the transaction is not a system
+    /// transaction.
+    SystemCallerNotAllowed,
+    /// The transaction is a system transaction, but its caller is not the system address.
+    InvalidSystemTxCaller { caller: Address },
+    /// The payload of the transaction exceeds the maximum payload size of the spec.
+    PayloadTooLarge { size: usize, max_size: usize },
+    /// The gas limit of the L1 message exceeds the maximum gas limit of the L1 message queue.
+    L1MessageGasLimitTooHigh { gas_limit: u64, max_gas_limit: u64 },
+}
+
+impl TransactionError for ScrollInvalidTransaction {}
+
+impl core::error::Error for ScrollInvalidTransaction {}
+
+impl fmt::Display for ScrollInvalidTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base(error) => error.fmt(f),
+            Self::L1Fee(error) => write!(f, "failed to compute the rollup fee: {error}"),
+            Self::InvalidL1BlockInfo(finding) => write!(f, "invalid L1 block info: {finding}"),
+            Self::UnaliasedL1Sender { sender } => {
+                write!(f, "L1 message sender {sender} is an unaliased L1 contract")
+            }
+            Self::L1SenderWithCode { sender } => {
+                write!(f, "L1 message sender {sender} has code deployed on L2")
+            }
+            Self::UnsupportedTxType { tx_type, spec_id } => {
+                write!(f, "transaction type {tx_type} is not supported in {spec_id:?}")
+            }
+            Self::BlobFieldsNotSupported => write!(f, "blob transaction fields are not supported"),
+            Self::SystemCallerNotAllowed => {
+                write!(f, "caller {SYSTEM_ADDRESS} is reserved for system transactions")
+            }
+            Self::InvalidSystemTxCaller { caller } => {
+                write!(f, "system transaction caller {caller} is not {SYSTEM_ADDRESS}")
+            }
+            Self::PayloadTooLarge { size, max_size } => {
+                write!(f, "transaction payload size {size} exceeds the maximum {max_size}")
+            }
+            Self::L1MessageGasLimitTooHigh { gas_limit, max_gas_limit } => {
+                write!(f, "L1 message gas limit {gas_limit} exceeds the maximum {max_gas_limit}")
+            }
+        }
+    }
+}
+
+impl From<InvalidTransaction> for ScrollInvalidTransaction {
+    fn from(value: InvalidTransaction) -> Self {
+        Self::Base(value)
+    }
+}
+
+impl From<L1FeeError> for ScrollInvalidTransaction {
+    fn from(value: L1FeeError) -> Self {
+        Self::L1Fee(value)
+    }
+}
+
+impl<DBError> From<ScrollInvalidTransaction> for EVMError<DBError, ScrollInvalidTransaction> {
+    fn from(value: ScrollInvalidTransaction) -> Self {
+        Self::Transaction(value)
+    }
+}
@@ -0,0 +1,211 @@
+//! Gas estimation aware of the Scroll rollup fee.
+//!
+//! The caller of a transaction pays for both the execution and the rollup fee of the transaction.
+//! A generic gas estimation reports a caller which cannot afford the rollup fee as running out of
+//! gas, [`ScrollEvm::estimate_gas`] reports it as [`EstimateGasError::InsufficientFundsForL1Fee`].
+
+use crate::{
+    error::ScrollInvalidTransaction,
+    exec::{ScrollContextTr, ScrollError},
+    instructions::ScrollInstructions,
+    ScrollEvm, ScrollTransaction,
+};
+use core::fmt;
+
+use revm::{
+    context::{
+        result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction},
+        Cfg, ContextSetters, ContextTr, Transaction, TxEnv,
+    },
+    handler::PrecompileProvider,
+    interpreter::{
+        gas::calculate_initial_tx_gas_for_tx, interpreter::EthInterpreter, InterpreterResult,
+    },
+    primitives::{Address, Bytes, U256},
+    Database, ExecuteEvm,
+};
+
+/// The result of a gas estimation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollGasEstimate {
+    /// The lowest gas limit for which the transaction succeeds.
+    pub gas_limit: u64,
+    /// The rollup fee assumed for the transaction, zero for L1 messages.
+    pub l1_fee: U256,
+}
+
+/// The error returned by a gas estimation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EstimateGasError<DBError> {
+    /// The caller cannot afford the rollup fee of the transaction on top of its value and
+    /// intrinsic execution cost.
+    InsufficientFundsForL1Fee {
+        /// The rollup fee of the transaction.
+        l1_fee: U256,
+        /// The balance of the caller.
+        balance: U256,
+    },
+    /// The transaction reverts with the highest affordable gas limit.
+    Reverted {
+        /// The output of the transaction.
+        output: Bytes,
+        /// The gas used by the transaction.
+        gas_used: u64,
+    },
+    /// The transaction halts with the highest affordable gas limit.
+    Halted {
+        /// The reason of the halt.
+        reason: HaltReason,
+        /// The gas used by the transaction.
+        gas_used: u64,
+    },
+    /// The transaction is invalid or the database returned an error.
+    Evm(EVMError<DBError, ScrollInvalidTransaction>),
+}
+
+impl<DBError: fmt::Display> fmt::Display for EstimateGasError<DBError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientFundsForL1Fee { l1_fee, balance } => {
+                write!(f, "insufficient funds for rollup fee {l1_fee} with balance {balance}")
+            }
+            Self::Reverted { output, gas_used } => {
+                write!(f, "transaction reverted with output {output} after using {gas_used} gas")
+            }
+            Self::Halted { reason, gas_used } => {
+                write!(f, "transaction halted with {reason:?} after using {gas_used} gas")
+            }
+            Self::Evm(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<DBError: fmt::Debug + fmt::Display> core::error::Error for EstimateGasError<DBError> {}
+
+impl<DBError> From<EVMError<DBError, ScrollInvalidTransaction>> for EstimateGasError<DBError> {
+    fn from(value: EVMError<DBError, ScrollInvalidTransaction>) -> Self {
+        Self::Evm(value)
+    }
+}
+
+impl<CTX, INSP, PRECOMPILE>
+    ScrollEvm<CTX, INSP, ScrollInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: ScrollContextTr<Tx = ScrollTransaction<TxEnv>> + ContextSetters,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    /// Estimates the lowest gas limit for which the transaction succeeds.
+    ///
+    /// The gas limit of the transaction is the upper bound of the search, lowered to the highest
+    /// gas limit the caller can afford once the value and the rollup fee are paid. From FEYNMAN,
+    /// the estimate is never lower than the EIP-7623 floor gas of the transaction.
+    ///
+    /// Transactions are executed without being committed.
+    pub fn estimate_gas(
+        &mut self,
+        tx: ScrollTransaction<TxEnv>,
+    ) -> Result<ScrollGasEstimate, EstimateGasError<<CTX::Db as Database>::Error>> {
+        let caller = tx.caller();
+        let value = tx.value();
+        let max_fee_per_gas = U256::from(tx.max_fee_per_gas());
+        let cfg = self.0.ctx.cfg();
+        let floor_gas = if cfg.is_eip7623_disabled() {
+            0
+        } else {
+            calculate_initial_tx_gas_for_tx(&tx, cfg.spec().into()).floor_gas
+        };
+
+        // lower the gas limit until the caller can afford the transaction. The rollup fee is only
+        // known once the caller can pay for the value and the execution.
+        let mut hi = tx.gas_limit();
+        let mut l1_fee_shortfall = None;
+        let (gas_used, l1_fee) = loop {
+            match self.transact_with_gas_limit(&tx, hi) {
+                Ok(ExecutionResult::Success { gas_used, .. }) => {
+                    let l1_fee = self.tx_fees().map(|fees| fees.l1_fee).unwrap_or_default();
+                    break (gas_used, l1_fee);
+                }
+                Ok(ExecutionResult::Revert { gas_used, output }) => {
+                    return Err(EstimateGasError::Reverted { output, gas_used })
+                }
+                Ok(ExecutionResult::Halt { reason, gas_used }) => {
+                    return Err(EstimateGasError::Halted { reason, gas_used })
+                }
+                Err(
+                    err @ EVMError::Transaction(ScrollInvalidTransaction::Base(
+                        InvalidTransaction::LackOfFundForMaxFee { .. },
+                    )),
+                ) => {
+                    let balance = self.caller_balance(caller)?;
+                    // the caller cannot afford the value, whatever the rollup fee.
+                    if balance < value {
+                        return Err(EstimateGasError::Evm(err));
+                    }
+                    let l1_fee = self.tx_fees().map(|fees| fees.l1_fee);
+                    let insufficient_funds = |l1_fee: Option<U256>| match l1_fee {
+                        Some(l1_fee) => {
+                            EstimateGasError::InsufficientFundsForL1Fee { l1_fee, balance }
+                        }
+                        None => EstimateGasError::Evm(err),
+                    };
+
+                    let Some(spendable) =
+                        balance.checked_sub(value.saturating_add(l1_fee.unwrap_or_default()))
+                    else {
+                        return Err(insufficient_funds(l1_fee));
+                    };
+                    let cap = spendable
+                        .checked_div(max_fee_per_gas)
+                        .map_or(u64::MAX, |cap| cap.saturating_to());
+                    if cap >= hi {
+                        return Err(insufficient_funds(l1_fee));
+                    }
+                    hi = cap;
+                    l1_fee_shortfall = l1_fee.or(l1_fee_shortfall);
+                }
+                Err(err) => {
+                    // the gas limit left once the rollup fee is paid does not cover the intrinsic
+                    // gas of the transaction.
+                    if let (Some(l1_fee), EVMError::Transaction(_)) = (l1_fee_shortfall, &err) {
+                        let balance = self.caller_balance(caller)?;
+                        return Err(EstimateGasError::InsufficientFundsForL1Fee {
+                            l1_fee,
+                            balance,
+                        });
+                    }
+                    return Err(err.into());
+                }
+            }
+        };
+
+        // the transaction cannot succeed with less gas than it used or than its floor gas.
+        let mut lo = gas_used.max(floor_gas).saturating_sub(1);
+        while lo + 1 < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.transact_with_gas_limit(&tx, mid) {
+                Ok(result) if result.is_success() => hi = mid,
+                Ok(_) | Err(EVMError::Transaction(_)) => lo = mid,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(ScrollGasEstimate { gas_limit: hi, l1_fee })
+    }
+
+    /// Executes the transaction with the provided gas limit, without committing it.
+    fn transact_with_gas_limit(
+        &mut self,
+        tx: &ScrollTransaction<TxEnv>,
+        gas_limit: u64,
+    ) -> Result<ExecutionResult<HaltReason>, ScrollError<CTX>> {
+        let mut tx = tx.clone();
+        tx.base.gas_limit = gas_limit;
+        self.transact(tx).map(|result| result.result)
+    }
+
+    /// Returns the balance of the caller in the database.
+    fn caller_balance(&mut self, caller: Address) -> Result<U256, ScrollError<CTX>> {
+        let account = self.0.ctx.db_mut().basic(caller).map_err(EVMError::Database)?;
+        Ok(account.map(|account| account.balance).unwrap_or_default())
+    }
+}
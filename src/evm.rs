@@ -1,5 +1,8 @@
 use crate::{
-    exec::ScrollContextTr, instructions::ScrollInstructions, precompile::ScrollPrecompileProvider,
+    chain::{ScrollChainTr, ScrollTxFees},
+    exec::ScrollContextTr,
+    instructions::ScrollInstructions,
+    precompile::ScrollPrecompileProvider,
 };
 
 use revm::{
@@ -25,7 +28,10 @@ pub struct ScrollEvm<
 impl<CTX: ScrollContextTr, INSP>
     ScrollEvm<CTX, INSP, ScrollInstructions<EthInterpreter, CTX>, ScrollPrecompileProvider>
 {
-    pub fn new(ctx: CTX, inspector: INSP) -> Self {
+    /// Returns a new [`ScrollEvm`] for the context. The L1 block info cached in the chain context
+    /// is invalidated, as it may have been fetched from another database.
+    pub fn new(mut ctx: CTX, inspector: INSP) -> Self {
+        ctx.chain_mut().scroll_chain_mut().invalidate_l1_block_info();
         let spec = ctx.cfg().spec();
         Self(Evm {
             ctx,
@@ -54,6 +60,16 @@ impl<CTX, INSP, I, P> ScrollEvm<CTX, INSP, I, P> {
     }
 }
 
+impl<CTX: ScrollContextTr, INSP, I, P> ScrollEvm<CTX, INSP, I, P> {
+    /// Returns the fees charged for the last executed transaction.
+    ///
+    /// Returns None if the transaction is an L1 message or failed validation. The value is only
+    /// meaningful if the transaction succeeded.
+    pub fn tx_fees(&self) -> Option<&ScrollTxFees> {
+        self.0.ctx.chain().scroll_chain().tx_fees.as_ref()
+    }
+}
+
 impl<CTX, INSP, I, P> EvmTr for ScrollEvm<CTX, INSP, I, P>
 where
     CTX: ContextTr,
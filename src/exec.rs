@@ -1,5 +1,6 @@
 use crate::{
-    handler::ScrollHandler, instructions::ScrollInstructions, l1block::L1BlockInfo,
+    chain::ScrollChainTr, error::ScrollInvalidTransaction, handler::ScrollHandler,
+    instructions::ScrollInstructions, l1block::L1_GAS_PRICE_ORACLE_ADDRESS,
     transaction::ScrollTxTr, ScrollEvm, ScrollSpecId,
 };
 
@@ -21,26 +22,27 @@ use revm_inspector::{InspectCommitEvm, InspectEvm, Inspector, InspectorHandler,
 
 pub trait ScrollContextTr:
     ContextTr<
-    Journal: JournalTr<State = EvmState>,
+    Journal: JournalTr<State = EvmState> + JournalExt,
     Tx: ScrollTxTr,
     Cfg: Cfg<Spec = ScrollSpecId>,
-    Chain = L1BlockInfo,
+    Chain: ScrollChainTr,
 >
 {
 }
 
 impl<T> ScrollContextTr for T where
     T: ContextTr<
-        Journal: JournalTr<State = EvmState>,
+        Journal: JournalTr<State = EvmState> + JournalExt,
         Tx: ScrollTxTr,
         Cfg: Cfg<Spec = ScrollSpecId>,
-        Chain = L1BlockInfo,
+        Chain: ScrollChainTr,
     >
 {
 }
 
 /// Type alias for the error type of the ScrollEvm.
-pub type ScrollError<CTX> = EVMError<<<CTX as ContextTr>::Db as Database>::Error>;
+pub type ScrollError<CTX> =
+    EVMError<<<CTX as ContextTr>::Db as Database>::Error, ScrollInvalidTransaction>;
 
 impl<CTX, INSP, PRECOMPILE> ExecuteEvm
     for ScrollEvm<CTX, INSP, ScrollInstructions<EthInterpreter, CTX>, PRECOMPILE>
@@ -56,6 +58,8 @@ where
 
     fn set_block(&mut self, block: Self::Block) {
         self.0.ctx.set_block(block);
+        // the L1 block info cached for the previous block may be stale.
+        self.0.ctx.chain_mut().scroll_chain_mut().invalidate_l1_block_info();
     }
 
     fn transact_one(&mut self, tx: Self::Tx) -> Result<Self::ExecutionResult, Self::Error> {
@@ -65,7 +69,16 @@ where
     }
 
     fn finalize(&mut self) -> Self::State {
-        self.0.ctx.journal_mut().finalize()
+        let state = self.0.ctx.journal_mut().finalize();
+        // the cached L1 block info is stale if the transactions updated the gas price oracle.
+        if let Some(oracle) = state.get(&L1_GAS_PRICE_ORACLE_ADDRESS) {
+            self.0
+                .ctx
+                .chain_mut()
+                .scroll_chain_mut()
+                .invalidate_l1_block_info_on_oracle_write(&oracle.storage);
+        }
+        state
     }
 
     fn replay(
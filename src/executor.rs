@@ -0,0 +1,288 @@
+//! Block execution.
+//!
+//! The [`ScrollBlockExecutor`] executes the transactions of a block on a [`ScrollEvm`]. From
+//! Feynman, the parent block hash is stored in the EIP-2935 history storage contract before the
+//! transactions are executed. The L1 messages of the block are executed first, followed by the L2
+//! transactions, and the state changes of each transaction are committed to the database.
+
+use crate::{
+    block::{validate_l1_messages, L1MessageOrderError},
+    chain::ScrollTxFees,
+    exec::{ScrollContextTr, ScrollError},
+    instructions::{ScrollInstructions, HISTORY_STORAGE_ADDRESS},
+    transaction::SYSTEM_ADDRESS,
+    ScrollEvm, ScrollSpecId,
+};
+use core::fmt;
+use std::vec::Vec;
+
+use revm::{
+    context::{
+        result::{ExecutionResult, HaltReason},
+        Block, Cfg, ContextSetters, ContextTr, Transaction,
+    },
+    handler::{EvmTr, PrecompileProvider, SystemCallTx},
+    interpreter::{interpreter::EthInterpreter, InterpreterResult},
+    primitives::{hash_map::Entry, B256},
+    state::EvmState,
+    DatabaseCommit, ExecuteCommitEvm, ExecuteEvm, SystemCallEvm,
+};
+
+/// An error that occurred while executing a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrollBlockExecutionError<E> {
+    /// The L1 messages of the block are invalid.
+    L1MessageOrder(L1MessageOrderError),
+    /// The gas limit of the transaction at the index exceeds the gas left in the block.
+    BlockGasLimitExceeded {
+        /// The index of the transaction.
+        index: usize,
+        /// The gas limit of the transaction.
+        gas_limit: u64,
+        /// The gas left in the block.
+        available: u64,
+    },
+    /// The transaction at the index could not be executed.
+    Transaction {
+        /// The index of the transaction.
+        index: usize,
+        /// The error of the transaction.
+        error: E,
+    },
+    /// The EIP-2935 history storage system call could not be executed.
+    SystemCall(E),
+    /// The EIP-2935 history storage system call reverted or halted.
+    SystemCallFailed(ExecutionResult<HaltReason>),
+    /// A pre- or post-block hook failed.
+    Hook(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ScrollBlockExecutionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::L1MessageOrder(error) => write!(f, "invalid L1 messages: {error}"),
+            Self::BlockGasLimitExceeded { index, gas_limit, available } => write!(
+                f,
+                "transaction at index {index} has gas limit {gas_limit}, exceeding the {available} gas left in the block"
+            ),
+            Self::Transaction { index, error } => {
+                write!(f, "failed to execute transaction at index {index}: {error}")
+            }
+            Self::SystemCall(error) => {
+                write!(f, "failed to execute the history storage system call: {error}")
+            }
+            Self::SystemCallFailed(result) => {
+                write!(f, "history storage system call failed: {result:?}")
+            }
+            Self::Hook(error) => write!(f, "block hook failed: {error}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for ScrollBlockExecutionError<E> {}
+
+/// The execution of a transaction of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScrollTxExecution {
+    /// The result of the transaction.
+    pub result: ExecutionResult<HaltReason>,
+    /// The gas used by the transactions of the block up to and including the transaction.
+    pub cumulative_gas_used: u64,
+    /// The fees charged for the transaction, None if the transaction is an L1 message.
+    pub fees: Option<ScrollTxFees>,
+}
+
+/// The output of the execution of a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollBlockExecutionOutput {
+    /// The executions of the transactions of the block, in order.
+    pub txs: Vec<ScrollTxExecution>,
+    /// The gas used by the transactions of the block.
+    pub gas_used: u64,
+    /// The state changes of the block, including the system call.
+    pub state: EvmState,
+    /// The queue index following the last L1 message of the block.
+    pub next_l1_message_queue_index: u64,
+}
+
+/// The hooks called by the [`ScrollBlockExecutor`] around the execution of a block.
+///
+/// The state changes of the hooks must be committed to the database of the EVM, they are not
+/// included in the state changes of the block output.
+pub trait ScrollBlockHooks<EVM: ExecuteEvm> {
+    /// Called after the block environment is set, before the EIP-2935 system call and the
+    /// transactions of the block, e.g. to apply the state changes of a hardfork.
+    fn pre_block(&mut self, _evm: &mut EVM) -> Result<(), EVM::Error> {
+        Ok(())
+    }
+
+    /// Called after the transactions of the block are executed.
+    fn post_block(
+        &mut self,
+        _evm: &mut EVM,
+        _output: &mut ScrollBlockExecutionOutput,
+    ) -> Result<(), EVM::Error> {
+        Ok(())
+    }
+}
+
+impl<EVM: ExecuteEvm> ScrollBlockHooks<EVM> for () {}
+
+/// Executes the transactions of a block on a [`ScrollEvm`].
+pub struct ScrollBlockExecutor<EVM, H = ()> {
+    evm: EVM,
+    hooks: H,
+}
+
+impl<EVM> ScrollBlockExecutor<EVM> {
+    /// Returns a new [`ScrollBlockExecutor`] executing blocks on the provided EVM.
+    pub fn new(evm: EVM) -> Self {
+        Self { evm, hooks: () }
+    }
+}
+
+impl<EVM, H> ScrollBlockExecutor<EVM, H> {
+    /// Consumes self and returns a new executor with the provided hooks.
+    pub fn with_hooks<NH>(self, hooks: NH) -> ScrollBlockExecutor<EVM, NH> {
+        ScrollBlockExecutor { evm: self.evm, hooks }
+    }
+
+    /// Returns a reference to the EVM.
+    pub fn evm(&self) -> &EVM {
+        &self.evm
+    }
+
+    /// Returns a mutable reference to the EVM.
+    pub fn evm_mut(&mut self) -> &mut EVM {
+        &mut self.evm
+    }
+
+    /// Returns a reference to the hooks.
+    pub fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Returns a mutable reference to the hooks.
+    pub fn hooks_mut(&mut self) -> &mut H {
+        &mut self.hooks
+    }
+
+    /// Consumes self and returns the EVM.
+    pub fn into_evm(self) -> EVM {
+        self.evm
+    }
+}
+
+impl<CTX, INSP, PRECOMPILE, H>
+    ScrollBlockExecutor<
+        ScrollEvm<CTX, INSP, ScrollInstructions<EthInterpreter, CTX>, PRECOMPILE>,
+        H,
+    >
+where
+    CTX: ScrollContextTr<Db: DatabaseCommit, Tx: SystemCallTx> + ContextSetters,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+    H: ScrollBlockHooks<ScrollEvm<CTX, INSP, ScrollInstructions<EthInterpreter, CTX>, PRECOMPILE>>,
+{
+    /// Executes the ordered transactions of the block with the provided parent block hash,
+    /// committing the state changes of each transaction to the database.
+    ///
+    /// The `next_l1_message_queue_index` is the queue index following the last L1 message included
+    /// before the block, i.e. the [`ScrollBlockExecutionOutput::next_l1_message_queue_index`] of
+    /// the parent block, which the first L1 message of the block must continue.
+    pub fn execute_block(
+        &mut self,
+        block: CTX::Block,
+        parent_hash: B256,
+        next_l1_message_queue_index: u64,
+        txs: Vec<CTX::Tx>,
+    ) -> Result<ScrollBlockExecutionOutput, ScrollBlockExecutionError<ScrollError<CTX>>> {
+        // the L1 messages must come first and continue the L1 message queue.
+        let next_l1_message_queue_index =
+            validate_l1_messages(&block, &txs, next_l1_message_queue_index)
+                .map_err(ScrollBlockExecutionError::L1MessageOrder)?;
+
+        let block_gas_limit = block.gas_limit();
+        let is_genesis = block.number().is_zero();
+        self.evm.set_block(block);
+        self.hooks.pre_block(&mut self.evm).map_err(ScrollBlockExecutionError::Hook)?;
+
+        let mut output = ScrollBlockExecutionOutput::default();
+
+        // EIP-2935: store the parent block hash in the history storage contract.
+        let spec = self.evm.ctx_ref().cfg().spec();
+        if spec.is_enabled_in(ScrollSpecId::FEYNMAN) && !is_genesis {
+            let result = self
+                .evm
+                .transact_system_call_with_caller(
+                    SYSTEM_ADDRESS,
+                    HISTORY_STORAGE_ADDRESS,
+                    parent_hash.0.into(),
+                )
+                .map_err(ScrollBlockExecutionError::SystemCall)?;
+            let state = self.evm.finalize();
+            if !result.is_success() {
+                return Err(ScrollBlockExecutionError::SystemCallFailed(result));
+            }
+            merge_state(&mut output.state, &state);
+            self.evm.commit(state);
+        }
+
+        for (index, tx) in txs.into_iter().enumerate() {
+            let available = block_gas_limit - output.gas_used;
+            let gas_limit = tx.gas_limit();
+            if gas_limit > available {
+                return Err(ScrollBlockExecutionError::BlockGasLimitExceeded {
+                    index,
+                    gas_limit,
+                    available,
+                });
+            }
+
+            let result = self
+                .evm
+                .transact(tx)
+                .map_err(|error| ScrollBlockExecutionError::Transaction { index, error })?;
+            output.gas_used += result.result.gas_used();
+            output.txs.push(ScrollTxExecution {
+                result: result.result,
+                cumulative_gas_used: output.gas_used,
+                fees: self.evm.tx_fees().cloned(),
+            });
+            merge_state(&mut output.state, &result.state);
+            self.evm.commit(result.state);
+        }
+        output.next_l1_message_queue_index = next_l1_message_queue_index;
+
+        self.hooks
+            .post_block(&mut self.evm, &mut output)
+            .map_err(ScrollBlockExecutionError::Hook)?;
+        Ok(output)
+    }
+}
+
+/// Merges the state changes of a transaction into the state changes of the block, keeping the
+/// original values of the storage slots before the block.
+fn merge_state(block_state: &mut EvmState, state: &EvmState) {
+    for (address, account) in state {
+        match block_state.entry(*address) {
+            Entry::Occupied(mut entry) => {
+                let previous = entry.get_mut();
+                previous.info = account.info.clone();
+                previous.status |= account.status;
+                for (key, slot) in &account.storage {
+                    match previous.storage.entry(*key) {
+                        Entry::Occupied(mut previous_slot) => {
+                            previous_slot.get_mut().present_value = slot.present_value;
+                        }
+                        Entry::Vacant(previous_slot) => {
+                            previous_slot.insert(slot.clone());
+                        }
+                    }
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(account.clone());
+            }
+        }
+    }
+}
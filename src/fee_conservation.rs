@@ -0,0 +1,371 @@
+//! Fee conservation checks.
+//!
+//! The caller of a transaction is debited `gas_limit * effective_gas_price + l1_fee` before
+//! execution, refunded the unused gas after execution and the fee recipients are credited the
+//! execution fee and the l1 fee. Any difference between the debit and the credit plus the refund
+//! silently mints or burns ETH.
+//!
+//! The refund and the credits are the amounts recorded by the handler in [`ScrollTxFees`], such
+//! that the EIP-7623 floor is accounted for. The value transfers of the execution are recorded by
+//! the [`ValueTransferInspector`] and set apart from the balance changes of the accounts, so the
+//! invariant holds if the total balance of the accounts is unchanged by the transaction, the
+//! debit equals the refund plus the credits, and the caller and the fee recipients are charged
+//! and credited the expected amounts on top of their transfers.
+//!
+//! [`ScrollTxFees`]: crate::ScrollTxFees
+
+use crate::{
+    chain::ScrollChainTr,
+    exec::{ScrollContextTr, ScrollError},
+    instructions::ScrollInstructions,
+    transaction::ScrollTxTr,
+    ScrollEvm,
+};
+use std::{vec, vec::Vec};
+
+use revm::{
+    context::{result::ResultAndState, Block, ContextSetters, ContextTr, Transaction},
+    context_interface::result::EVMError,
+    handler::PrecompileProvider,
+    interpreter::{
+        interpreter::EthInterpreter, CallInputs, CallOutcome, CreateInputs, CreateOutcome,
+        InterpreterResult,
+    },
+    primitives::{Address, U256},
+    state::EvmState,
+    Database, ExecuteEvm,
+};
+use revm_inspector::{InspectEvm, Inspector};
+
+/// A value transfer of the execution of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValueTransfer {
+    /// The sender of the value.
+    pub from: Address,
+    /// The recipient of the value.
+    pub to: Address,
+    /// The transferred value.
+    pub value: U256,
+}
+
+/// An inspector recording the value transfers of the calls and creations of a transaction which
+/// are not reverted.
+///
+/// `SELFDESTRUCT` is disabled on Scroll, so the calls and creations are the only transfers.
+#[derive(Clone, Debug, Default)]
+pub struct ValueTransferInspector {
+    /// The transfers of the frames being executed, the last one being the current frame.
+    frames: Vec<Vec<ValueTransfer>>,
+    /// The transfers of the last transaction.
+    transfers: Vec<ValueTransfer>,
+}
+
+impl ValueTransferInspector {
+    /// Returns the value transfers of the last transaction.
+    pub fn transfers(&self) -> &[ValueTransfer] {
+        &self.transfers
+    }
+
+    /// Starts a frame, clearing the transfers of the previous transaction on the first frame.
+    fn frame_start(&mut self, transfer: Option<ValueTransfer>) {
+        if self.frames.is_empty() {
+            self.transfers.clear();
+        }
+        self.frames.push(transfer.into_iter().collect());
+    }
+
+    /// Ends the current frame, dropping its transfers if it did not succeed.
+    fn frame_end(&mut self, is_ok: bool) {
+        let transfers = self.frames.pop().unwrap_or_default();
+        if !is_ok {
+            return;
+        }
+        match self.frames.last_mut() {
+            Some(parent) => parent.extend(transfers),
+            None => self.transfers.extend(transfers),
+        }
+    }
+}
+
+impl<CTX> Inspector<CTX, EthInterpreter> for ValueTransferInspector {
+    fn call(&mut self, _context: &mut CTX, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let transfer = inputs.transfer_value().filter(|value| !value.is_zero()).map(|value| {
+            ValueTransfer { from: inputs.transfer_from(), to: inputs.transfer_to(), value }
+        });
+        self.frame_start(transfer);
+        None
+    }
+
+    fn call_end(&mut self, _context: &mut CTX, _inputs: &CallInputs, outcome: &mut CallOutcome) {
+        self.frame_end(outcome.result.is_ok());
+    }
+
+    fn create(&mut self, _context: &mut CTX, _inputs: &mut CreateInputs) -> Option<CreateOutcome> {
+        // the address of the created contract is only known once the frame ends.
+        self.frame_start(None);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut CTX,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+    ) {
+        if let (Some(address), Some(frame)) = (outcome.address, self.frames.last_mut()) {
+            if !inputs.value.is_zero() {
+                frame.insert(
+                    0,
+                    ValueTransfer { from: inputs.caller, to: address, value: inputs.value },
+                );
+            }
+        }
+        self.frame_end(outcome.result.is_ok());
+    }
+}
+
+/// The fee flows expected for a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollFeeFlow {
+    /// The caller of the transaction.
+    pub caller: Address,
+    /// The amount debited from the caller before execution.
+    pub debit: U256,
+    /// The amount refunded to the caller for the unused gas.
+    pub refund: U256,
+    /// The amounts credited to the fee recipients.
+    pub credits: Vec<(Address, U256)>,
+}
+
+impl ScrollFeeFlow {
+    /// Returns the total amount credited to the fee recipients.
+    pub fn credit(&self) -> U256 {
+        self.credits.iter().fold(U256::ZERO, |acc, (_, credit)| acc.saturating_add(*credit))
+    }
+}
+
+/// A violation of the fee conservation invariant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FeeMismatch {
+    /// The total balance of the accounts changed.
+    Supply {
+        /// The sum of the balance increases.
+        increase: U256,
+        /// The sum of the balance decreases.
+        decrease: U256,
+    },
+    /// The debit of the caller is not the refund plus the credits of the fee recipients.
+    Flow {
+        /// The amount debited from the caller.
+        debit: U256,
+        /// The amount refunded to the caller.
+        refund: U256,
+        /// The total amount credited to the fee recipients.
+        credit: U256,
+    },
+    /// The caller was not charged the expected amount, i.e. the debit minus the refund, on top of
+    /// its value transfers.
+    Debit {
+        /// The caller of the transaction.
+        caller: Address,
+        /// The expected charge of the caller.
+        expected: U256,
+        /// The value received by the caller during execution.
+        received: U256,
+        /// The value sent by the caller during execution.
+        sent: U256,
+        /// The balance of the caller before the transaction.
+        pre_balance: U256,
+        /// The balance of the caller after the transaction.
+        post_balance: U256,
+    },
+    /// A fee recipient was not credited the expected amount on top of its value transfers.
+    Credit {
+        /// The fee recipient.
+        recipient: Address,
+        /// The expected credit.
+        expected: U256,
+        /// The value received by the recipient during execution.
+        received: U256,
+        /// The value sent by the recipient during execution.
+        sent: U256,
+        /// The balance of the recipient before the transaction.
+        pre_balance: U256,
+        /// The balance of the recipient after the transaction.
+        post_balance: U256,
+    },
+}
+
+/// The result of checking the fee conservation of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeConservationReport {
+    /// The expected fee flows.
+    pub flow: ScrollFeeFlow,
+    /// The value transfers of the execution.
+    pub transfers: Vec<ValueTransfer>,
+    /// The violations of the invariant.
+    pub mismatches: Vec<FeeMismatch>,
+}
+
+impl FeeConservationReport {
+    /// Returns true if the fees of the transaction are conserved.
+    pub fn is_conserved(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Returns the value received and the value sent by the account through the transfers.
+fn transferred(transfers: &[ValueTransfer], address: Address) -> (U256, U256) {
+    transfers.iter().fold((U256::ZERO, U256::ZERO), |(received, sent), transfer| {
+        match (transfer.to == address, transfer.from == address) {
+            (true, false) => (received.saturating_add(transfer.value), sent),
+            (false, true) => (received, sent.saturating_add(transfer.value)),
+            _ => (received, sent),
+        }
+    })
+}
+
+/// Checks the state changes of a transaction against the expected fee flows and the value
+/// transfers of its execution.
+///
+/// The database must hold the state before the transaction. The charge of a caller which is also
+/// a fee recipient is not checked, as it is netted with its credit.
+pub fn check_fee_conservation<DB: Database>(
+    db: &mut DB,
+    flow: ScrollFeeFlow,
+    transfers: Vec<ValueTransfer>,
+    state: &EvmState,
+) -> Result<FeeConservationReport, DB::Error> {
+    let mut balances = |address: Address| -> Result<(U256, U256), DB::Error> {
+        let pre_balance = db.basic(address)?.map(|info| info.balance).unwrap_or_default();
+        let post_balance = state.get(&address).map_or(pre_balance, |account| account.info.balance);
+        Ok((pre_balance, post_balance))
+    };
+
+    let mut mismatches = Vec::new();
+
+    let (mut increase, mut decrease) = (U256::ZERO, U256::ZERO);
+    for address in state.keys() {
+        let (pre, post) = balances(*address)?;
+        increase = increase.saturating_add(post.saturating_sub(pre));
+        decrease = decrease.saturating_add(pre.saturating_sub(post));
+    }
+    if increase != decrease {
+        mismatches.push(FeeMismatch::Supply { increase, decrease });
+    }
+
+    let (debit, refund, credit) = (flow.debit, flow.refund, flow.credit());
+    if refund.checked_add(credit) != Some(debit) {
+        mismatches.push(FeeMismatch::Flow { debit, refund, credit });
+    }
+
+    if !flow.credits.iter().any(|(recipient, _)| *recipient == flow.caller) {
+        let caller = flow.caller;
+        let expected = debit.saturating_sub(refund);
+        let (received, sent) = transferred(&transfers, caller);
+        let (pre_balance, post_balance) = balances(caller)?;
+        let expected_balance = pre_balance
+            .checked_add(received)
+            .and_then(|balance| balance.checked_sub(sent))
+            .and_then(|balance| balance.checked_sub(expected));
+        if expected_balance != Some(post_balance) {
+            mismatches.push(FeeMismatch::Debit {
+                caller,
+                expected,
+                received,
+                sent,
+                pre_balance,
+                post_balance,
+            });
+        }
+    }
+
+    let mut credits: Vec<(Address, U256)> = Vec::new();
+    for (recipient, credit) in &flow.credits {
+        match credits.iter_mut().find(|(address, _)| address == recipient) {
+            Some((_, total)) => *total = total.saturating_add(*credit),
+            None => credits.push((*recipient, *credit)),
+        }
+    }
+    for (recipient, expected) in credits {
+        let (received, sent) = transferred(&transfers, recipient);
+        let (pre_balance, post_balance) = balances(recipient)?;
+        let mut expected_balance = pre_balance
+            .checked_add(received)
+            .and_then(|balance| balance.checked_sub(sent))
+            .and_then(|balance| balance.checked_add(expected));
+        // a caller which is also a fee recipient pays its charge out of the credit.
+        if recipient == flow.caller {
+            expected_balance = expected_balance
+                .and_then(|balance| balance.checked_sub(debit.saturating_sub(refund)));
+        }
+        if expected_balance != Some(post_balance) {
+            mismatches.push(FeeMismatch::Credit {
+                recipient,
+                expected,
+                received,
+                sent,
+                pre_balance,
+                post_balance,
+            });
+        }
+    }
+
+    Ok(FeeConservationReport { flow, transfers, mismatches })
+}
+
+impl<CTX, PRECOMPILE>
+    ScrollEvm<CTX, ValueTransferInspector, ScrollInstructions<EthInterpreter, CTX>, PRECOMPILE>
+where
+    CTX: ScrollContextTr + ContextSetters,
+    PRECOMPILE: PrecompileProvider<CTX, Output = InterpreterResult>,
+{
+    /// Executes the transaction without committing it and checks that its fees are conserved.
+    ///
+    /// The database must hold the state before the transaction, i.e. previous transactions must
+    /// be committed.
+    pub fn transact_with_fee_check(
+        &mut self,
+        tx: CTX::Tx,
+    ) -> Result<(ResultAndState, FeeConservationReport), ScrollError<CTX>> {
+        let ctx = &self.0.ctx;
+        let caller = tx.caller();
+        let gas_limit = tx.gas_limit();
+        let is_l1_msg = tx.is_l1_msg();
+        let effective_gas_price = U256::from(tx.effective_gas_price(ctx.block().basefee() as u128));
+        let beneficiary = ctx.block().beneficiary();
+        let fee_routing = ctx.chain().scroll_chain().fee_routing;
+
+        let result = self.inspect_one_tx(tx);
+        let state = self.finalize();
+        let result = ResultAndState::new(result?, state);
+
+        // l1 messages are paid for on L1.
+        let flow = if is_l1_msg {
+            ScrollFeeFlow { caller, credits: vec![(beneficiary, U256::ZERO)], ..Default::default() }
+        } else {
+            // the refund and the execution fee recorded by the handler account for the floor.
+            let fees = self.tx_fees().cloned().unwrap_or_default();
+            let credits = match fee_routing {
+                Some(routing) => vec![
+                    (routing.execution_fee_recipient, fees.execution_fee),
+                    (routing.l1_fee_recipient, fees.l1_fee),
+                ],
+                None => vec![(beneficiary, fees.execution_fee.saturating_add(fees.l1_fee))],
+            };
+            ScrollFeeFlow {
+                caller,
+                debit: effective_gas_price
+                    .saturating_mul(U256::from(gas_limit))
+                    .saturating_add(fees.l1_fee),
+                refund: fees.refund,
+                credits,
+            }
+        };
+
+        let transfers = self.0.inspector.transfers().to_vec();
+        let report = check_fee_conservation(self.0.ctx.db_mut(), flow, transfers, &result.state)
+            .map_err(EVMError::Database)?;
+        Ok((result, report))
+    }
+}
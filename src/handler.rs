@@ -1,10 +1,16 @@
 //! Handler related to Scroll chain.
 
-use crate::{exec::ScrollContextTr, l1block::L1BlockInfo, transaction::ScrollTxTr, ScrollSpecId};
-use std::{boxed::Box, string::ToString};
+use crate::{
+    chain::{ScrollChainTr, ScrollTxFees},
+    error::ScrollInvalidTransaction,
+    exec::ScrollContextTr,
+    l1block::L1_GAS_PRICE_ORACLE_ADDRESS,
+    rollup_fee::{tx_rollup_fee, RollupFeeModel},
+    transaction::ScrollTxTr,
+    validation::{validate_caller_balance, validate_l1_message_caller, validate_tx_env},
+};
 
 use revm::{
-    bytecode::Bytecode,
     context::{
         result::{HaltReason, InvalidTransaction},
         Block, Cfg, ContextTr, JournalTr, Transaction,
@@ -15,9 +21,11 @@ use revm::{
     interpreter::{
         interpreter::EthInterpreter, interpreter_action::FrameInit, Gas, InitialAndFloorGas,
     },
-    primitives::U256,
+    primitives::{Address, B256, U256},
+    state::{AccountInfo, Bytecode, EvmStorage},
+    Database,
 };
-use revm_inspector::{Inspector, InspectorEvmTr, InspectorHandler};
+use revm_inspector::{Inspector, InspectorEvmTr, InspectorHandler, JournalExt};
 
 /// The Scroll handler.
 pub struct ScrollHandler<EVM, ERROR, FRAME> {
@@ -36,35 +44,153 @@ impl<EVM, ERROR, FRAME> Default for ScrollHandler<EVM, ERROR, FRAME> {
     }
 }
 
+impl<EVM, ERROR, FRAME> ScrollHandler<EVM, ERROR, FRAME>
+where
+    EVM: EvmTr<Context: ScrollContextTr>,
+    ERROR: EvmTrError<EVM> + From<ScrollInvalidTransaction>,
+{
+    /// Returns the rollup fee of the transaction, computed by the [`RollupFeeModel`] of the chain.
+    ///
+    /// The fee is computed once per transaction and recorded in the chain context, such that the
+    /// amount deducted from the caller and the amount rewarded to the beneficiary always agree.
+    fn tx_l1_cost(&self, evm: &mut EVM) -> Result<U256, ERROR> {
+        let ctx = evm.ctx();
+        if let Some(tx_fees) = &ctx.chain().scroll_chain().tx_fees {
+            return Ok(tx_fees.l1_fee);
+        }
+
+        let chain = ctx.chain().scroll_chain();
+        let l1_fee =
+            tx_rollup_fee(&chain.fee_model, &chain.l1_block_info, ctx.tx(), ctx.cfg().spec())
+                .map_err(ScrollInvalidTransaction::from)?;
+
+        let chain = ctx.chain_mut().scroll_chain_mut();
+        chain.tx_fees = Some(ScrollTxFees {
+            l1_fee,
+            l1_block_info: chain.l1_block_info.clone(),
+            execution_fee: U256::ZERO,
+            refund: U256::ZERO,
+        });
+        Ok(l1_fee)
+    }
+}
+
+/// A database reading the storage of the L1 gas price oracle from its journaled storage first,
+/// such that the `L1BlockInfo` reflects the writes of the previous transactions of the block without
+/// loading the oracle in the journal of the current transaction.
+struct JournaledOracleDb<'a, DB> {
+    oracle_storage: &'a EvmStorage,
+    db: &'a mut DB,
+}
+
+impl<DB: Database> Database for JournaledOracleDb<'_, DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if address == L1_GAS_PRICE_ORACLE_ADDRESS {
+            if let Some(slot) = self.oracle_storage.get(&index) {
+                return Ok(slot.present_value);
+            }
+        }
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
 /// Configure the handler for the Scroll chain.
 ///
 /// The trait modifies the following handlers:
-/// - `pre_execution` - Adds a hook to load `L1BlockInfo` from the database such that it can be used
-///   to calculate the L1 cost of a transaction.
+/// - `validate_env` - Rejects, with a [`ScrollInvalidTransaction`] and in order, ordinary
+///   transactions using the system address as caller, the transaction types and blob fields not
+///   supported by the spec, and payloads exceeding the maximum payload size of the spec or the
+///   limits of the L1 message queue, before the Ethereum environment checks.
+/// - `pre_execution` - Adds a hook to load `L1BlockInfo` through the [`RollupFeeModel`] of the chain
+///   such that it can be used to calculate the L1 cost of a transaction. The `L1BlockInfo` is cached
+///   for the block until the L1 gas price oracle is written to.
 /// - `validate_against_state_and_deduct_caller` - Overrides the logic to deduct the max transaction
 ///   fee, including the L1 fee, from the caller's balance.
 /// - `last_frame_result` - Overrides the logic for gas refund in the case the transaction is a L1
 ///   message.
 /// - `refund` - Overrides the logic for gas refund in the case the transaction is a L1 message.
 /// - `post_execution.reward_beneficiary` - Overrides the logic to reward the beneficiary with the
-///   gas fee and skip rewarding in case the transaction is a L1 message.
+///   gas fee, or the configured fee recipients, and skip rewarding in case the transaction is a L1
+///   message.
 impl<EVM, ERROR, FRAME> Handler for ScrollHandler<EVM, ERROR, FRAME>
 where
     EVM: EvmTr<Context: ScrollContextTr, Frame = FRAME>,
-    ERROR: EvmTrError<EVM> + From<InvalidTransaction>,
+    ERROR: EvmTrError<EVM> + From<InvalidTransaction> + From<ScrollInvalidTransaction>,
     FRAME: FrameTr<FrameResult = FrameResult, FrameInit = FrameInit>,
 {
     type Evm = EVM;
     type Error = ERROR;
     type HaltReason = HaltReason;
 
+    #[inline]
+    fn validate_env(&self, evm: &mut Self::Evm) -> Result<(), Self::Error> {
+        // clear the fees of the previous transaction, such that they are not reported for a
+        // transaction failing validation.
+        evm.ctx().chain_mut().scroll_chain_mut().tx_fees = None;
+
+        let ctx = evm.ctx_ref();
+        validate_tx_env(
+            ctx.tx(),
+            ctx.chain().scroll_chain().max_tx_payload_size.as_ref(),
+            ctx.cfg().spec(),
+        )?;
+        self.mainnet.validate_env(evm)
+    }
+
     #[inline]
     fn pre_execution(&self, evm: &mut Self::Evm) -> Result<u64, Self::Error> {
-        // only load the L1BlockInfo for txs that are not l1 messages.
+        // clear the fees of the previous transaction.
+        evm.ctx().chain_mut().scroll_chain_mut().tx_fees = None;
+
+        // only load the L1BlockInfo for txs that are not l1 messages. The L1BlockInfo is cached
+        // across the transactions of a block and only fetched again after a write to the oracle.
         if !evm.ctx().tx().is_l1_msg() && !evm.ctx().tx().is_system_tx() {
+            // the previous transactions of the block may have written to the oracle without the
+            // state being finalized, in which case the writes are only found in the journal.
+            let oracle_storage = evm
+                .ctx()
+                .journal_mut()
+                .evm_state()
+                .get(&L1_GAS_PRICE_ORACLE_ADDRESS)
+                .map(|account| account.storage.clone())
+                .unwrap_or_default();
+            evm.ctx()
+                .chain_mut()
+                .scroll_chain_mut()
+                .invalidate_l1_block_info_on_oracle_write(&oracle_storage);
+
             let spec = evm.ctx().cfg().spec();
-            let l1_block_info = L1BlockInfo::try_fetch(evm.ctx().db_mut(), spec)?;
-            *evm.ctx().chain_mut() = l1_block_info;
+            let block_number = evm.ctx().block().number();
+            if !evm.ctx().chain().scroll_chain().is_l1_block_info_cached(block_number, spec) {
+                let fee_model = evm.ctx().chain().scroll_chain().fee_model.clone();
+                let mut db =
+                    JournaledOracleDb { oracle_storage: &oracle_storage, db: evm.ctx().db_mut() };
+                let l1_block_info = fee_model.fetch_l1_block_info(&mut db, spec)?;
+                evm.ctx().chain_mut().scroll_chain_mut().cache_l1_block_info(
+                    l1_block_info,
+                    block_number,
+                    spec,
+                );
+            }
+
+            // reject the transaction if the L1BlockInfo violates the policy of the chain.
+            if let Some(finding) = evm.ctx().chain().scroll_chain().l1_block_info_rejection() {
+                return Err(ScrollInvalidTransaction::InvalidL1BlockInfo(finding.clone()).into());
+            }
         }
 
         self.validate_against_state_and_deduct_caller(evm)?;
@@ -94,75 +220,30 @@ where
         }
 
         // process rollup fee
-        let ctx = evm.ctx();
         if !is_l1_msg && !is_system_tx {
-            let l1_block_info = ctx.chain().clone();
-            let Some(rlp_bytes) = ctx.tx().rlp_bytes() else {
-                return Err(ERROR::from_string(
-                    "[SCROLL] Failed to load transaction rlp_bytes.".to_string(),
-                ));
-            };
-
             // Deduct l1 fee from caller.
-            let tx_l1_cost = l1_block_info.calculate_tx_l1_cost(
-                rlp_bytes,
-                spec,
-                ctx.tx().compression_ratio(),
-                ctx.tx().compressed_size(),
-            );
-            let caller_account = ctx.journal_mut().load_account(caller)?;
-            if tx_l1_cost.gt(&caller_account.info.balance) {
-                return Err(InvalidTransaction::LackOfFundForMaxFee {
-                    fee: tx_l1_cost.into(),
-                    balance: caller_account.info.balance.into(),
-                }
-                .into());
-            }
+            let tx_l1_cost = self.tx_l1_cost(evm)?;
+            let caller_account = evm.ctx().journal_mut().load_account(caller)?;
+            validate_caller_balance(tx_l1_cost, caller_account.info.balance)?;
             caller_account.data.info.balance =
                 caller_account.data.info.balance.saturating_sub(tx_l1_cost);
         }
 
         // execute l1 msg checks
         if is_l1_msg {
+            let strict_l1_sender = evm.ctx().chain().scroll_chain().strict_l1_sender.clone();
+
             // Load caller's account.
-            let (tx, journal) = ctx.tx_journal_mut();
+            let (tx, journal) = evm.ctx().tx_journal_mut();
             let mut caller_account = journal.load_account(caller)?;
 
-            // Note: we skip the balance check at pre-execution level if the transaction is a
-            // L1 message and Euclid is enabled. This means the L1 message will reach execution
-            // stage in revm and revert with `OutOfFunds` in the first frame, but still be included
-            // in the block.
-            let skip_balance_check = tx.is_l1_msg() && spec.is_enabled_in(ScrollSpecId::EUCLID);
-            if !skip_balance_check {
-                let max_balance_spending = tx.max_balance_spending()?;
-                if max_balance_spending > caller_account.info.balance {
-                    return Err(InvalidTransaction::LackOfFundForMaxFee {
-                        fee: Box::new(max_balance_spending),
-                        balance: Box::new(caller_account.info.balance),
-                    }
-                    .into());
-                }
-            }
-
-            // EIP-3607: Reject transactions from senders with deployed code.
-            //
-            // We check the sender of the L1 message is a EOA on the L2.
-            // If the sender is a (delegated) EOA on the L1, it should be a (delegated) EOA
-            // on the L2.
-            // If the sender is a contract on the L1, address aliasing assures with high probability
-            // that the L2 sender would be an EOA.
-            if !is_eip3607_disabled {
-                let caller_info = &caller_account.info;
-                let bytecode = match caller_info.code.as_ref() {
-                    Some(bytecode) => bytecode,
-                    None => &Bytecode::default(),
-                };
-                // Allow EOAs whose code is a valid delegation designation,
-                // i.e. 0xef0100 || address, to continue to originate transactions.
-                if !bytecode.is_empty() && !bytecode.is_eip7702() {
-                    return Err(InvalidTransaction::RejectCallerWithCode.into());
-                }
-            }
+            validate_l1_message_caller(
+                tx,
+                &caller_account.info,
+                strict_l1_sender.as_ref(),
+                spec,
+                is_eip3607_disabled,
+            )?;
 
             // Bump the nonce for calls. Nonce for CREATE will be bumped in `make_create_frame`.
             if tx.kind().is_call() {
@@ -230,51 +311,77 @@ where
         post_execution::refund(spec, exec_result.gas_mut(), eip7702_refund)
     }
 
-    fn reward_beneficiary(
+    fn reimburse_caller(
         &self,
         evm: &mut Self::Evm,
         exec_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
     ) -> Result<(), Self::Error> {
+        self.mainnet.reimburse_caller(evm, exec_result)?;
+
+        // l1 messages are paid for on L1.
+        if evm.ctx().tx().is_l1_msg() {
+            return Ok(());
+        }
+
+        // record the amount refunded to the caller, the gas is already adjusted for the floor.
+        self.tx_l1_cost(evm)?;
         let ctx = evm.ctx();
+        let effective_gas_price =
+            U256::from(ctx.tx().effective_gas_price(ctx.block().basefee() as u128));
+        let gas = exec_result.gas();
+        let refund = effective_gas_price
+            .saturating_mul(U256::from(gas.remaining().saturating_add(gas.refunded() as u64)));
+        if let Some(tx_fees) = &mut ctx.chain_mut().scroll_chain_mut().tx_fees {
+            tx_fees.refund = refund;
+        }
+
+        Ok(())
+    }
 
+    fn reward_beneficiary(
+        &self,
+        evm: &mut Self::Evm,
+        exec_result: &mut <<Self::Evm as EvmTr>::Frame as FrameTr>::FrameResult,
+    ) -> Result<(), Self::Error> {
         // If the transaction is an L1 message, we do not need to reward the beneficiary as the
         // transaction has already been paid for on L1.
-        if ctx.tx().is_l1_msg() {
+        if evm.ctx().tx().is_l1_msg() {
             return Ok(());
         }
 
+        // calculate the L1 cost of the transaction.
+        let l1_cost = self.tx_l1_cost(evm)?;
+
         // fetch the effective gas price.
+        let ctx = evm.ctx();
         let block = ctx.block();
         let effective_gas_price = U256::from(ctx.tx().effective_gas_price(block.basefee() as u128));
 
         // load beneficiary's account.
         let beneficiary = block.beneficiary();
 
-        // calculate the L1 cost of the transaction.
-        let l1_cost = if !ctx.tx().is_system_tx() {
-            let l1_block_info = ctx.chain().clone();
-            let Some(rlp_bytes) = &ctx.tx().rlp_bytes() else {
-                return Err(ERROR::from_string(
-                    "[SCROLL] Failed to load transaction rlp_bytes.".to_string(),
-                ));
-            };
-            l1_block_info.calculate_tx_l1_cost(
-                rlp_bytes,
-                ctx.cfg().spec(),
-                ctx.tx().compression_ratio(),
-                ctx.tx().compressed_size(),
-            )
-        } else {
-            U256::from(0)
-        };
-
-        // reward the beneficiary with the gas fee including the L1 cost of the transaction and mark
-        // the account as touched.
         let gas = exec_result.gas();
+        let execution_fee = effective_gas_price.saturating_mul(U256::from(gas.used()));
+
+        let fee_routing = ctx.chain().scroll_chain().fee_routing;
+        match fee_routing {
+            // credit each fee to its recipient and mark the accounts as touched.
+            Some(routing) => {
+                ctx.journal_mut().balance_incr(routing.execution_fee_recipient, execution_fee)?;
+                ctx.journal_mut().balance_incr(routing.l1_fee_recipient, l1_cost)?;
+            }
+            // reward the beneficiary with the gas fee including the L1 cost of the transaction and
+            // mark the account as touched.
+            None => {
+                let reward = execution_fee.saturating_add(l1_cost);
+                ctx.journal_mut().balance_incr(beneficiary, reward)?;
+            }
+        }
 
-        let reward =
-            effective_gas_price.saturating_mul(U256::from(gas.used())).saturating_add(l1_cost);
-        ctx.journal_mut().balance_incr(beneficiary, reward)?;
+        // record the execution fee of the transaction.
+        if let Some(tx_fees) = &mut ctx.chain_mut().scroll_chain_mut().tx_fees {
+            tx_fees.execution_fee = execution_fee;
+        }
 
         Ok(())
     }
@@ -287,7 +394,7 @@ where
         Frame = EthFrame<EthInterpreter>,
         Inspector: Inspector<<<Self as Handler>::Evm as EvmTr>::Context, EthInterpreter>,
     >,
-    ERROR: EvmTrError<EVM>,
+    ERROR: EvmTrError<EVM> + From<ScrollInvalidTransaction>,
 {
     type IT = EthInterpreter;
 }
@@ -297,31 +404,45 @@ mod tests {
     use super::*;
     use crate::{
         builder::ScrollBuilder,
+        chain::{
+            MaxTxPayloadSize, ScrollFeeRouting, DEFAULT_MAX_L1_MESSAGE_GAS_LIMIT,
+            L2_TX_FEE_VAULT_ADDRESS, SEQUENCER_MAX_TX_PAYLOAD_SIZE,
+        },
+        error::ScrollInvalidTransaction,
+        l1block::{
+            L1BlockInfo, L1BlockInfoField, L1BlockInfoFinding, L1BlockInfoFindingAction,
+            L1BlockInfoPolicy, L1_COMMIT_SCALAR_SLOT,
+        },
         test_utils::{
             context, ScrollContextTestUtils, BENEFICIARY, CALLER, L1_DATA_COST,
             MIN_TRANSACTION_COST,
         },
+        transaction::L1_MESSAGE_TYPE,
+        ScrollSpecId,
     };
-    use std::boxed::Box;
+    use std::{boxed::Box, vec, vec::Vec};
 
     use revm::{
         context::result::EVMError,
         handler::EthFrame,
         interpreter::{CallOutcome, InstructionResult, InterpreterResult},
+        primitives::{address, Address, Bytes},
     };
 
     #[test]
     fn test_validate_lacking_funds() -> Result<(), Box<dyn core::error::Error>> {
         let ctx = context();
         let mut evm = ctx.build_scroll();
-        let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
         let err = handler.validate_against_state_and_deduct_caller(&mut evm).unwrap_err();
         assert_eq!(
             err,
-            EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee {
-                fee: Box::new(U256::from(21000)),
-                balance: Box::default()
-            })
+            EVMError::Transaction(ScrollInvalidTransaction::Base(
+                InvalidTransaction::LackOfFundForMaxFee {
+                    fee: Box::new(U256::from(21000)),
+                    balance: Box::default()
+                }
+            ))
         );
 
         Ok(())
@@ -331,10 +452,10 @@ mod tests {
     fn test_load_account() -> Result<(), Box<dyn core::error::Error>> {
         let ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
         let mut evm = ctx.build_scroll();
-        let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
         handler.pre_execution(&mut evm)?;
 
-        let l1_block_info = evm.ctx().chain.clone();
+        let l1_block_info = evm.ctx().chain.l1_block_info.clone();
         assert_ne!(l1_block_info, L1BlockInfo::default());
 
         Ok(())
@@ -345,7 +466,7 @@ mod tests {
         let ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
 
         let mut evm = ctx.build_scroll();
-        let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
         handler.pre_execution(&mut evm)?;
 
         let ctx = evm.ctx_mut();
@@ -361,7 +482,8 @@ mod tests {
         let ctx = context();
 
         let mut evm = ctx.build_scroll();
-        let mut handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let mut handler =
+            ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
         let mut gas = Gas::new(21000);
         gas.set_refund(10);
         gas.set_spent(10);
@@ -385,7 +507,7 @@ mod tests {
         let ctx = context();
 
         let mut evm = ctx.build_scroll();
-        let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
         let mut gas = Gas::new(21000);
         gas.set_refund(10);
         gas.set_spent(10);
@@ -410,7 +532,7 @@ mod tests {
         let ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
 
         let mut evm = ctx.build_scroll();
-        let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
         let gas = Gas::new_spent(21000);
         let mut result = FrameResult::Call(CallOutcome::new(
             InterpreterResult {
@@ -430,13 +552,144 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_reward_fee_recipients() -> Result<(), Box<dyn core::error::Error>> {
+        const EXECUTION_FEE_RECIPIENT: Address =
+            address!("0x0000000000000000000000000000000000000003");
+        let ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST).modify_chain_chained(
+            |chain| {
+                chain.fee_routing = Some(ScrollFeeRouting {
+                    l1_fee_recipient: L2_TX_FEE_VAULT_ADDRESS,
+                    execution_fee_recipient: EXECUTION_FEE_RECIPIENT,
+                })
+            },
+        );
+
+        let mut evm = ctx.build_scroll();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        let gas = Gas::new_spent(21000);
+        let mut result = FrameResult::Call(CallOutcome::new(
+            InterpreterResult {
+                result: InstructionResult::Return,
+                output: Default::default(),
+                gas,
+            },
+            0..0,
+        ));
+        handler.pre_execution(&mut evm)?;
+        handler.reward_beneficiary(&mut evm, &mut result)?;
+
+        let ctx = evm.ctx_mut();
+        let vault = ctx.journal_mut().load_account(L2_TX_FEE_VAULT_ADDRESS)?;
+        assert_eq!(vault.info.balance, L1_DATA_COST);
+        let execution_fee_recipient = ctx.journal_mut().load_account(EXECUTION_FEE_RECIPIENT)?;
+        assert_eq!(execution_fee_recipient.info.balance, MIN_TRANSACTION_COST);
+        let beneficiary = ctx.journal_mut().load_account(BENEFICIARY)?;
+        assert_eq!(beneficiary.info.balance, U256::ZERO);
+
+        Ok(())
+    }
+
     #[test]
     fn test_transaction_pre_execution() -> Result<(), Box<dyn core::error::Error>> {
         let ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
 
         let mut evm = ctx.build_scroll();
-        let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        handler.pre_execution(&mut evm)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_l1_block_info_policy() -> Result<(), Box<dyn core::error::Error>> {
+        let finding = L1BlockInfoFinding::ZeroScalar { field: L1BlockInfoField::L1CommitScalar };
+        let ctx = context()
+            .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST)
+            .with_gas_oracle_config([(L1_COMMIT_SCALAR_SLOT, U256::ZERO)].into())
+            .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        let with_policy = |zero_scalar| {
+            let policy = L1BlockInfoPolicy { zero_scalar, ..Default::default() };
+            ctx.clone().modify_chain_chained(|chain| chain.l1_block_info_policy = policy)
+        };
+
+        // findings are recorded but ignored by default.
+        let mut evm = ctx.clone().build_scroll();
+        handler.pre_execution(&mut evm)?;
+        assert_eq!(evm.ctx().chain().l1_block_info_findings, [finding.clone()]);
+        assert_eq!(evm.ctx().chain().l1_block_info_warnings().count(), 0);
+
+        let mut evm = with_policy(L1BlockInfoFindingAction::Warn).build_scroll();
         handler.pre_execution(&mut evm)?;
+        assert_eq!(evm.ctx().chain().l1_block_info_warnings().collect::<Vec<_>>(), [&finding]);
+
+        let mut evm = with_policy(L1BlockInfoFindingAction::Reject).build_scroll();
+        let err = handler.pre_execution(&mut evm).unwrap_err();
+        assert_eq!(
+            err,
+            EVMError::Transaction(ScrollInvalidTransaction::InvalidL1BlockInfo(finding))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_env_max_tx_payload_size() -> Result<(), Box<dyn core::error::Error>> {
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        let with_max_size = |max_size| {
+            context().modify_chain_chained(|chain| {
+                chain.max_tx_payload_size = Some(MaxTxPayloadSize::uniform(max_size))
+            })
+        };
+
+        // the payload size is not checked by default, such that historical blocks can be replayed.
+        let mut evm = context()
+            .with_tx_payload(Bytes::from(vec![1; SEQUENCER_MAX_TX_PAYLOAD_SIZE + 1]))
+            .build_scroll();
+        handler.validate_env(&mut evm)?;
+
+        // the test transaction has 4 RLP bytes.
+        let mut evm = with_max_size(4).build_scroll();
+        handler.validate_env(&mut evm)?;
+
+        let mut evm = with_max_size(3).build_scroll();
+        assert_eq!(
+            handler.validate_env(&mut evm),
+            Err(EVMError::Transaction(ScrollInvalidTransaction::PayloadTooLarge {
+                size: 4,
+                max_size: 3
+            }))
+        );
+
+        // L1 messages are checked against the limits of the L1 message queue instead.
+        let l1_message = |gas_limit, input: Bytes| {
+            with_max_size(3).modify_tx_chained(|tx| {
+                tx.base.tx_type = L1_MESSAGE_TYPE;
+                tx.base.gas_limit = gas_limit;
+                tx.base.data = input;
+            })
+        };
+        let mut evm = l1_message(21_000 + 16 * 4, Bytes::from(vec![1; 4])).build_scroll();
+        handler.validate_env(&mut evm)?;
+
+        let mut evm = l1_message(21_000 + 16 * 4 - 1, Bytes::from(vec![1; 4])).build_scroll();
+        assert_eq!(
+            handler.validate_env(&mut evm),
+            Err(EVMError::Transaction(ScrollInvalidTransaction::PayloadTooLarge {
+                size: 4,
+                max_size: 3
+            }))
+        );
+
+        let mut evm = l1_message(DEFAULT_MAX_L1_MESSAGE_GAS_LIMIT + 1, Bytes::new()).build_scroll();
+        assert_eq!(
+            handler.validate_env(&mut evm),
+            Err(EVMError::Transaction(ScrollInvalidTransaction::L1MessageGasLimitTooHigh {
+                gas_limit: DEFAULT_MAX_L1_MESSAGE_GAS_LIMIT + 1,
+                max_gas_limit: DEFAULT_MAX_L1_MESSAGE_GAS_LIMIT
+            }))
+        );
 
         Ok(())
     }
@@ -14,7 +14,8 @@ use revm::{
 };
 use std::rc::Rc;
 
-const HISTORY_STORAGE_ADDRESS: Address = address!("0x0000F90827F1C53a10cb7A02335B175320002935");
+/// The address of the EIP-2935 history storage system contract.
+pub const HISTORY_STORAGE_ADDRESS: Address = address!("0x0000F90827F1C53a10cb7A02335B175320002935");
 const HISTORY_SERVE_WINDOW: u64 = 8191;
 const DIFFICULTY: U256 = U256::ZERO;
 
@@ -1,8 +1,11 @@
 use crate::ScrollSpecId;
+use core::fmt;
+use std::vec::Vec;
 
 use revm::{
-    primitives::{address, Address, U256},
-    Database,
+    primitives::{address, Address, HashMap, U256},
+    state::{Account, EvmStorageSlot},
+    Database, DatabaseCommit,
 };
 
 // CONSTANTS
@@ -53,6 +56,282 @@ pub const PENALTY_FACTOR_SLOT: U256 = U256::from_limbs([10u64, 0, 0, 0]);
 
 const U64_MAX: U256 = U256::from_limbs([u64::MAX, 0, 0, 0]);
 
+// L1 FEE ERROR
+// ================================================================================================
+
+/// A field of the [`L1BlockInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1BlockInfoField {
+    L1BaseFee,
+    L1BaseFeeScalar,
+    L1BlobBaseFee,
+    L1CommitScalar,
+    L1BlobScalar,
+    CalldataGas,
+    PenaltyThreshold,
+    PenaltyFactor,
+}
+
+impl fmt::Display for L1BlockInfoField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::L1BaseFee => "l1_base_fee",
+            Self::L1BaseFeeScalar => "l1_base_fee_scalar",
+            Self::L1BlobBaseFee => "l1_blob_base_fee",
+            Self::L1CommitScalar => "l1_commit_scalar",
+            Self::L1BlobScalar => "l1_blob_scalar",
+            Self::CalldataGas => "calldata_gas",
+            Self::PenaltyThreshold => "penalty_threshold",
+            Self::PenaltyFactor => "penalty_factor",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An error that occurred while computing the rollup fee of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1FeeError {
+    /// The transaction is missing the RLP encoded bytes the rollup fee is computed from.
+    MissingRlpBytes,
+    /// A field of the [`L1BlockInfo`] required by the spec is missing.
+    MissingField { field: L1BlockInfoField, spec_id: ScrollSpecId },
+    /// The transaction compression ratio is required by the spec but was not provided.
+    MissingCompressionRatio { spec_id: ScrollSpecId },
+    /// The transaction compressed size is required by the spec but was not provided.
+    MissingCompressedSize { spec_id: ScrollSpecId },
+    /// The transaction compression ratio is below [`TX_L1_FEE_PRECISION`].
+    CompressionRatioBelowPrecision { compression_ratio: U256 },
+    /// The transaction compressed size is larger than the size of the transaction.
+    CompressedSizeExceedsTxSize { compressed_size: usize, tx_size: usize },
+}
+
+impl core::error::Error for L1FeeError {}
+
+impl fmt::Display for L1FeeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingRlpBytes => write!(f, "missing transaction rlp bytes"),
+            Self::MissingField { field, spec_id } => {
+                write!(f, "missing {field} in spec_id={spec_id:?}")
+            }
+            Self::MissingCompressionRatio { spec_id } => {
+                write!(f, "compression ratio should be set in spec_id={spec_id:?}")
+            }
+            Self::MissingCompressedSize { spec_id } => {
+                write!(f, "compressed size should be set in spec_id={spec_id:?}")
+            }
+            Self::CompressionRatioBelowPrecision { compression_ratio } => write!(
+                f,
+                "transaction compression ratio must be greater or equal to {TX_L1_FEE_PRECISION_U256:?} - compression ratio: {compression_ratio:?}"
+            ),
+            Self::CompressedSizeExceedsTxSize { compressed_size, tx_size } => write!(
+                f,
+                "transaction compressed size {compressed_size} must be less than or equal to the original size {tx_size}"
+            ),
+        }
+    }
+}
+
+/// Returns the value of an optional [`L1BlockInfo`] field or a [`L1FeeError::MissingField`].
+fn require(
+    value: Option<U256>,
+    field: L1BlockInfoField,
+    spec_id: ScrollSpecId,
+) -> Result<U256, L1FeeError> {
+    value.ok_or(L1FeeError::MissingField { field, spec_id })
+}
+
+/// An error that occurred while writing the [`L1BlockInfo`] to the database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum L1BlockInfoWriteError<DBError> {
+    /// The L1 block info is missing a field required by the spec.
+    L1Fee(L1FeeError),
+    /// The oracle account could not be loaded from the database.
+    Database(DBError),
+}
+
+impl<DBError: fmt::Display> fmt::Display for L1BlockInfoWriteError<DBError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::L1Fee(error) => error.fmt(f),
+            Self::Database(error) => write!(f, "failed to load the oracle account: {error}"),
+        }
+    }
+}
+
+impl<DBError: fmt::Debug + fmt::Display> core::error::Error for L1BlockInfoWriteError<DBError> {}
+
+// L1 BLOCK INFO VALIDATION
+// ================================================================================================
+
+/// The class of an [`L1BlockInfoFinding`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1BlockInfoFindingKind {
+    /// See [`L1BlockInfoFinding::ZeroScalar`].
+    ZeroScalar,
+    /// See [`L1BlockInfoFinding::PenaltyOutOfRange`].
+    PenaltyOutOfRange,
+    /// See [`L1BlockInfoFinding::MissingField`].
+    MissingField,
+    /// See [`L1BlockInfoFinding::Overflow`].
+    Overflow,
+}
+
+/// A finding of the [`L1BlockInfo::validate`] checks.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1BlockInfoFinding {
+    /// A scalar used by the spec is zero, the rollup fee does not charge for its component.
+    ZeroScalar { field: L1BlockInfoField },
+    /// A penalty parameter is outside of the range expected by the spec.
+    PenaltyOutOfRange { field: L1BlockInfoField, value: U256 },
+    /// A field required by the spec is missing.
+    MissingField { field: L1BlockInfoField },
+    /// The product of two fields overflows and is saturated by the rollup fee formula.
+    Overflow { lhs: L1BlockInfoField, rhs: L1BlockInfoField },
+}
+
+impl L1BlockInfoFinding {
+    /// Returns the class of the finding.
+    pub fn kind(&self) -> L1BlockInfoFindingKind {
+        match self {
+            Self::ZeroScalar { .. } => L1BlockInfoFindingKind::ZeroScalar,
+            Self::PenaltyOutOfRange { .. } => L1BlockInfoFindingKind::PenaltyOutOfRange,
+            Self::MissingField { .. } => L1BlockInfoFindingKind::MissingField,
+            Self::Overflow { .. } => L1BlockInfoFindingKind::Overflow,
+        }
+    }
+}
+
+impl fmt::Display for L1BlockInfoFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroScalar { field } => write!(f, "{field} is zero"),
+            Self::PenaltyOutOfRange { field, value } => {
+                write!(f, "{field} is out of range: {value}")
+            }
+            Self::MissingField { field } => write!(f, "missing {field}"),
+            Self::Overflow { lhs, rhs } => write!(f, "{lhs} * {rhs} overflows"),
+        }
+    }
+}
+
+/// The action taken by the handler for a class of [`L1BlockInfoFinding`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1BlockInfoFindingAction {
+    /// The finding is discarded.
+    #[default]
+    Ignore,
+    /// The finding is recorded in the chain context.
+    Warn,
+    /// Transactions paying a rollup fee are rejected.
+    Reject,
+}
+
+/// The actions taken by the handler for each class of [`L1BlockInfoFinding`]. All findings are
+/// ignored by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1BlockInfoPolicy {
+    /// The action for [`L1BlockInfoFinding::ZeroScalar`].
+    pub zero_scalar: L1BlockInfoFindingAction,
+    /// The action for [`L1BlockInfoFinding::PenaltyOutOfRange`].
+    pub penalty_out_of_range: L1BlockInfoFindingAction,
+    /// The action for [`L1BlockInfoFinding::MissingField`].
+    pub missing_field: L1BlockInfoFindingAction,
+    /// The action for [`L1BlockInfoFinding::Overflow`].
+    pub overflow: L1BlockInfoFindingAction,
+}
+
+impl L1BlockInfoPolicy {
+    /// Returns a policy taking the same action for all classes of findings.
+    pub const fn uniform(action: L1BlockInfoFindingAction) -> Self {
+        Self {
+            zero_scalar: action,
+            penalty_out_of_range: action,
+            missing_field: action,
+            overflow: action,
+        }
+    }
+
+    /// Returns the action for the finding.
+    pub fn action(&self, finding: &L1BlockInfoFinding) -> L1BlockInfoFindingAction {
+        match finding.kind() {
+            L1BlockInfoFindingKind::ZeroScalar => self.zero_scalar,
+            L1BlockInfoFindingKind::PenaltyOutOfRange => self.penalty_out_of_range,
+            L1BlockInfoFindingKind::MissingField => self.missing_field,
+            L1BlockInfoFindingKind::Overflow => self.overflow,
+        }
+    }
+}
+
+// L1 FEE BREAKDOWN
+// ================================================================================================
+
+/// The rollup fee formula used to compute the L1 fee of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum L1FeeFormula {
+    /// Pre-Curie formula, based on the calldata gas of the transaction.
+    Shanghai,
+    /// Curie formula, based on the commit and blob scalars.
+    Curie,
+    /// Feynman formula, based on the size of the transaction and its compression ratio.
+    Feynman,
+    /// Galileo formula, based on the compressed size of the transaction.
+    Galileo,
+}
+
+/// The itemized rollup fee of a transaction.
+///
+/// Components are expressed in the units of the formula they come from, i.e. scaled by
+/// [`TX_L1_FEE_PRECISION`], while the penalty is expressed in wei.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1FeeBreakdown {
+    /// The formula used to compute the fee.
+    pub formula: L1FeeFormula,
+    /// The execution component of the fee.
+    ///
+    /// Pre-Curie this is `data_gas * l1_base_fee * l1_base_fee_scalar`, for Curie the calldata
+    /// gas and post-Feynman `exec_scalar * l1_base_fee`.
+    pub component_exec: U256,
+    /// The data availability component of the fee.
+    ///
+    /// Zero pre-Curie, for Curie the blob gas of the transaction and post-Feynman
+    /// `blob_scalar * l1_blob_base_fee`.
+    pub component_blob: U256,
+    /// The fee per byte of the transaction, None if before Feynman.
+    pub fee_per_byte: Option<U256>,
+    /// The part of the fee due to the compression penalty, in wei.
+    pub penalty: U256,
+    /// The size of the transaction.
+    pub tx_size: usize,
+    /// The compressed size of the transaction, None if before Galileo.
+    pub compressed_size: Option<usize>,
+    /// Whether the fee was clamped to `u64::MAX`.
+    pub clamped: bool,
+}
+
+impl L1FeeBreakdown {
+    fn new(formula: L1FeeFormula, tx_size: usize) -> Self {
+        Self {
+            formula,
+            component_exec: U256::ZERO,
+            component_blob: U256::ZERO,
+            fee_per_byte: None,
+            penalty: U256::ZERO,
+            tx_size,
+            compressed_size: None,
+            clamped: false,
+        }
+    }
+}
+
 // L1 BLOCK INFO
 // ================================================================================================
 
@@ -137,37 +416,242 @@ impl L1BlockInfo {
         })
     }
 
+    /// Returns the L1 gas price oracle storage entries holding the L1 block info for the provided
+    /// spec.
+    ///
+    /// This is the inverse of [`L1BlockInfo::try_fetch`]. Post-FEYNMAN the exec scalar and the
+    /// compressed blob scalar are stored in the [`L1_COMMIT_SCALAR_SLOT`] and
+    /// [`L1_BLOB_SCALAR_SLOT`]. The penalty threshold is only required pre-GALILEO.
+    pub fn storage_entries(&self, spec_id: ScrollSpecId) -> Result<Vec<(U256, U256)>, L1FeeError> {
+        let mut entries = Vec::from([
+            (L1_BASE_FEE_SLOT, self.l1_base_fee),
+            (L1_OVERHEAD_SLOT, self.l1_fee_overhead),
+            (L1_SCALAR_SLOT, self.l1_base_fee_scalar),
+        ]);
+
+        if !spec_id.is_enabled_in(ScrollSpecId::CURIE) {
+            return Ok(entries);
+        }
+
+        entries.extend([
+            (
+                L1_BLOB_BASE_FEE_SLOT,
+                require(self.l1_blob_base_fee, L1BlockInfoField::L1BlobBaseFee, spec_id)?,
+            ),
+            (
+                L1_COMMIT_SCALAR_SLOT,
+                require(self.l1_commit_scalar, L1BlockInfoField::L1CommitScalar, spec_id)?,
+            ),
+            (
+                L1_BLOB_SCALAR_SLOT,
+                require(self.l1_blob_scalar, L1BlockInfoField::L1BlobScalar, spec_id)?,
+            ),
+        ]);
+
+        if !spec_id.is_enabled_in(ScrollSpecId::FEYNMAN) {
+            return Ok(entries);
+        }
+
+        let penalty_threshold = if spec_id.is_enabled_in(ScrollSpecId::GALILEO) {
+            self.penalty_threshold
+        } else {
+            Some(require(self.penalty_threshold, L1BlockInfoField::PenaltyThreshold, spec_id)?)
+        };
+        entries.extend(penalty_threshold.map(|threshold| (PENALTY_THRESHOLD_SLOT, threshold)));
+        entries.push((
+            PENALTY_FACTOR_SLOT,
+            require(self.penalty_factor, L1BlockInfoField::PenaltyFactor, spec_id)?,
+        ));
+
+        Ok(entries)
+    }
+
+    /// Validates the L1 block info against the rules of the spec.
+    ///
+    /// Returns the findings for scalars which are zero, penalty parameters which are out of
+    /// range, fields required by the spec which are missing and products of fields which overflow
+    /// in the rollup fee formula.
+    pub fn validate(&self, spec_id: ScrollSpecId) -> Vec<L1BlockInfoFinding> {
+        let mut findings = Vec::new();
+        let mut check_overflow = |lhs: (L1BlockInfoField, U256), rhs: (L1BlockInfoField, U256)| {
+            if lhs.1.checked_mul(rhs.1).is_none() {
+                findings.push(L1BlockInfoFinding::Overflow { lhs: lhs.0, rhs: rhs.0 });
+            }
+        };
+
+        if !spec_id.is_enabled_in(ScrollSpecId::CURIE) {
+            check_overflow(
+                (L1BlockInfoField::L1BaseFee, self.l1_base_fee),
+                (L1BlockInfoField::L1BaseFeeScalar, self.l1_base_fee_scalar),
+            );
+            if self.l1_base_fee_scalar.is_zero() {
+                findings.push(L1BlockInfoFinding::ZeroScalar {
+                    field: L1BlockInfoField::L1BaseFeeScalar,
+                });
+            }
+            return findings;
+        }
+
+        if let Some(commit_scalar) = self.l1_commit_scalar {
+            check_overflow(
+                (L1BlockInfoField::L1CommitScalar, commit_scalar),
+                (L1BlockInfoField::L1BaseFee, self.l1_base_fee),
+            );
+        }
+        if let (Some(blob_scalar), Some(blob_base_fee)) =
+            (self.l1_blob_scalar, self.l1_blob_base_fee)
+        {
+            check_overflow(
+                (L1BlockInfoField::L1BlobScalar, blob_scalar),
+                (L1BlockInfoField::L1BlobBaseFee, blob_base_fee),
+            );
+        }
+
+        let mut required = Vec::from([
+            (L1BlockInfoField::L1BlobBaseFee, self.l1_blob_base_fee),
+            (L1BlockInfoField::L1CommitScalar, self.l1_commit_scalar),
+            (L1BlockInfoField::L1BlobScalar, self.l1_blob_scalar),
+        ]);
+        if !spec_id.is_enabled_in(ScrollSpecId::FEYNMAN) {
+            required.push((L1BlockInfoField::CalldataGas, self.calldata_gas));
+        } else if !spec_id.is_enabled_in(ScrollSpecId::GALILEO) {
+            required.push((L1BlockInfoField::PenaltyThreshold, self.penalty_threshold));
+            required.push((L1BlockInfoField::PenaltyFactor, self.penalty_factor));
+        } else {
+            required.push((L1BlockInfoField::PenaltyFactor, self.penalty_factor));
+        }
+        for (field, value) in required {
+            if value.is_none() {
+                findings.push(L1BlockInfoFinding::MissingField { field });
+            }
+        }
+
+        for (field, value) in [
+            (L1BlockInfoField::L1CommitScalar, self.l1_commit_scalar),
+            (L1BlockInfoField::L1BlobScalar, self.l1_blob_scalar),
+        ] {
+            if value.is_some_and(|value| value.is_zero()) {
+                findings.push(L1BlockInfoFinding::ZeroScalar { field });
+            }
+        }
+
+        if !spec_id.is_enabled_in(ScrollSpecId::FEYNMAN) {
+            return findings;
+        }
+
+        // pre-GALILEO, the compression ratio of a transaction is never below the precision such
+        // that a lower threshold never applies the penalty, and a penalty factor below the
+        // precision discounts the fee.
+        let mut check_range = |field: L1BlockInfoField, value: Option<U256>, min: U256| {
+            if let Some(value) = value.filter(|value| *value < min) {
+                findings.push(L1BlockInfoFinding::PenaltyOutOfRange { field, value });
+            }
+        };
+        if !spec_id.is_enabled_in(ScrollSpecId::GALILEO) {
+            check_range(
+                L1BlockInfoField::PenaltyThreshold,
+                self.penalty_threshold,
+                TX_L1_FEE_PRECISION_U256,
+            );
+            check_range(
+                L1BlockInfoField::PenaltyFactor,
+                self.penalty_factor,
+                TX_L1_FEE_PRECISION_U256,
+            );
+        } else {
+            // post-GALILEO, the penalty factor divides the penalty term and zero is sanitized to
+            // one.
+            check_range(L1BlockInfoField::PenaltyFactor, self.penalty_factor, U256::ONE);
+        }
+
+        findings
+    }
+
+    /// Writes the L1 block info for the provided spec to the L1 gas price oracle storage.
+    ///
+    /// The oracle account information, e.g. its code, is preserved. The slots are committed as
+    /// changed from their current values, such that databases which only persist changed slots,
+    /// e.g. [`revm::database::State`], apply the write.
+    pub fn write_to<DB: Database + DatabaseCommit>(
+        &self,
+        db: &mut DB,
+        spec_id: ScrollSpecId,
+    ) -> Result<(), L1BlockInfoWriteError<DB::Error>> {
+        let entries = self.storage_entries(spec_id).map_err(L1BlockInfoWriteError::L1Fee)?;
+        let info = db
+            .basic(L1_GAS_PRICE_ORACLE_ADDRESS)
+            .map_err(L1BlockInfoWriteError::Database)?
+            .unwrap_or_default();
+
+        let mut oracle = Account::from(info);
+        for (slot, value) in entries {
+            let original = db
+                .storage(L1_GAS_PRICE_ORACLE_ADDRESS, slot)
+                .map_err(L1BlockInfoWriteError::Database)?;
+            oracle.storage.insert(slot, EvmStorageSlot::new_changed(original, value, 0));
+        }
+        oracle.mark_touch();
+
+        db.commit(HashMap::from_iter([(L1_GAS_PRICE_ORACLE_ADDRESS, oracle)]));
+        Ok(())
+    }
+
     /// Calculate the data gas for posting the transaction on L1. Calldata costs 16 gas per non-zero
     /// byte and 4 gas per zero byte.
-    pub fn data_gas(&self, input: &[u8], spec_id: ScrollSpecId) -> U256 {
+    ///
+    /// Returns a [`L1FeeError::MissingField`] if the blob fields are not set from Curie.
+    pub fn data_gas(&self, input: &[u8], spec_id: ScrollSpecId) -> Result<U256, L1FeeError> {
         if !spec_id.is_enabled_in(ScrollSpecId::CURIE) {
-            U256::from(input.iter().fold(0, |acc, byte| {
+            Ok(U256::from(input.iter().fold(0, |acc, byte| {
                 acc + if *byte == 0x00 { ZERO_BYTE_COST } else { NON_ZERO_BYTE_COST }
             }))
             .saturating_add(self.l1_fee_overhead)
-            .saturating_add(TX_L1_COMMIT_EXTRA_COST)
+            .saturating_add(TX_L1_COMMIT_EXTRA_COST))
         } else {
-            U256::from(input.len())
-                .saturating_mul(
-                    self.l1_blob_base_fee.expect("l1_blob_base_fee should be set in Curie"),
-                )
-                .saturating_mul(self.l1_blob_scalar.expect("l1_blob_scalar should be set in Curie"))
+            let l1_blob_base_fee =
+                require(self.l1_blob_base_fee, L1BlockInfoField::L1BlobBaseFee, spec_id)?;
+            let l1_blob_scalar =
+                require(self.l1_blob_scalar, L1BlockInfoField::L1BlobScalar, spec_id)?;
+            Ok(U256::from(input.len())
+                .saturating_mul(l1_blob_base_fee)
+                .saturating_mul(l1_blob_scalar))
         }
     }
 
-    fn calculate_tx_l1_cost_shanghai(&self, input: &[u8], spec_id: ScrollSpecId) -> U256 {
-        let tx_l1_gas = self.data_gas(input, spec_id);
-        tx_l1_gas
-            .saturating_mul(self.l1_base_fee)
-            .saturating_mul(self.l1_base_fee_scalar)
-            .wrapping_div(TX_L1_FEE_PRECISION_U256)
+    fn calculate_tx_l1_cost_shanghai(
+        &self,
+        input: &[u8],
+        spec_id: ScrollSpecId,
+    ) -> Result<(U256, L1FeeBreakdown), L1FeeError> {
+        let tx_l1_gas = self.data_gas(input, spec_id)?;
+        let component_exec =
+            tx_l1_gas.saturating_mul(self.l1_base_fee).saturating_mul(self.l1_base_fee_scalar);
+
+        let breakdown = L1FeeBreakdown {
+            component_exec,
+            ..L1FeeBreakdown::new(L1FeeFormula::Shanghai, input.len())
+        };
+        Ok((component_exec.wrapping_div(TX_L1_FEE_PRECISION_U256), breakdown))
     }
 
-    fn calculate_tx_l1_cost_curie(&self, input: &[u8], spec_id: ScrollSpecId) -> U256 {
+    fn calculate_tx_l1_cost_curie(
+        &self,
+        input: &[u8],
+        spec_id: ScrollSpecId,
+    ) -> Result<(U256, L1FeeBreakdown), L1FeeError> {
         // "commitScalar * l1BaseFee + blobScalar * _data.length * l1BlobBaseFee"
-        let blob_gas = self.data_gas(input, spec_id);
+        let calldata_gas = require(self.calldata_gas, L1BlockInfoField::CalldataGas, spec_id)?;
+        let blob_gas = self.data_gas(input, spec_id)?;
 
-        self.calldata_gas.unwrap().saturating_add(blob_gas).wrapping_div(TX_L1_FEE_PRECISION_U256)
+        let breakdown = L1FeeBreakdown {
+            component_exec: calldata_gas,
+            component_blob: blob_gas,
+            ..L1FeeBreakdown::new(L1FeeFormula::Curie, input.len())
+        };
+        Ok((
+            calldata_gas.saturating_add(blob_gas).wrapping_div(TX_L1_FEE_PRECISION_U256),
+            breakdown,
+        ))
     }
 
     fn calculate_tx_l1_cost_feynman(
@@ -175,7 +659,7 @@ impl L1BlockInfo {
         input: &[u8],
         spec_id: ScrollSpecId,
         compression_ratio: U256,
-    ) -> U256 {
+    ) -> Result<(U256, L1FeeBreakdown), L1FeeError> {
         // rollup_fee(tx) = size(tx) * (component_exec + component_blob) * penalty(tx)
         //
         // - size(tx): denotes the size of the signed tx.
@@ -204,30 +688,20 @@ impl L1BlockInfo {
         // compression_ratio, penalty_threshold, penalty_factor, penalty are all scaled
         // by TX_L1_FEE_PRECISION_U256 (1e9) to avoid losing precision.
 
-        assert!(
-            compression_ratio >= TX_L1_FEE_PRECISION_U256,
-            "transaction compression ratio must be greater or equal to {TX_L1_FEE_PRECISION_U256:?} - compression ratio: {compression_ratio:?}"
-        );
-
-        let exec_scalar = self
-            .l1_commit_scalar
-            .unwrap_or_else(|| panic!("missing exec scalar in spec_id={spec_id:?}"));
-
-        let compressed_blob_scalar = self
-            .l1_blob_scalar
-            .unwrap_or_else(|| panic!("missing l1 blob scalar in spec_id={spec_id:?}"));
-
-        let l1_blob_base_fee = self
-            .l1_blob_base_fee
-            .unwrap_or_else(|| panic!("missing l1 blob base fee in spec_id={spec_id:?}"));
-
-        let penalty_threshold = self
-            .penalty_threshold
-            .unwrap_or_else(|| panic!("missing penalty threshold in spec_id={spec_id:?}"));
+        if compression_ratio < TX_L1_FEE_PRECISION_U256 {
+            return Err(L1FeeError::CompressionRatioBelowPrecision { compression_ratio });
+        }
 
-        let penalty_factor = self
-            .penalty_factor
-            .unwrap_or_else(|| panic!("missing penalty factor in spec_id={spec_id:?}"));
+        let exec_scalar =
+            require(self.l1_commit_scalar, L1BlockInfoField::L1CommitScalar, spec_id)?;
+        let compressed_blob_scalar =
+            require(self.l1_blob_scalar, L1BlockInfoField::L1BlobScalar, spec_id)?;
+        let l1_blob_base_fee =
+            require(self.l1_blob_base_fee, L1BlockInfoField::L1BlobBaseFee, spec_id)?;
+        let penalty_threshold =
+            require(self.penalty_threshold, L1BlockInfoField::PenaltyThreshold, spec_id)?;
+        let penalty_factor =
+            require(self.penalty_factor, L1BlockInfoField::PenaltyFactor, spec_id)?;
 
         let tx_size = U256::from(input.len());
 
@@ -241,11 +715,21 @@ impl L1BlockInfo {
             penalty_factor
         };
 
-        tx_size
+        let base_fee = tx_size.saturating_mul(fee_per_byte).wrapping_div(TX_L1_FEE_PRECISION_U256);
+        let l1_cost = tx_size
             .saturating_mul(fee_per_byte)
             .saturating_mul(penalty)
             .wrapping_div(TX_L1_FEE_PRECISION_U256) // account for scalars
-            .wrapping_div(TX_L1_FEE_PRECISION_U256) // account for penalty
+            .wrapping_div(TX_L1_FEE_PRECISION_U256); // account for penalty
+
+        let breakdown = L1FeeBreakdown {
+            component_exec,
+            component_blob,
+            fee_per_byte: Some(fee_per_byte),
+            penalty: l1_cost.saturating_sub(base_fee),
+            ..L1FeeBreakdown::new(L1FeeFormula::Feynman, input.len())
+        };
+        Ok((l1_cost, breakdown))
     }
 
     fn calculate_tx_l1_cost_galileo(
@@ -253,7 +737,7 @@ impl L1BlockInfo {
         tx_size: usize, // size of the original rlp-encoded transaction
         spec_id: ScrollSpecId,
         compressed_size: usize, // size of the compressed rlp-encoded transaction
-    ) -> U256 {
+    ) -> Result<(U256, L1FeeBreakdown), L1FeeError> {
         // Post Galileo rollup fee formula:
         // rollup_fee(tx) = fee_per_byte * compressed_size(tx) * (1 + penalty(tx)) / PRECISION
         //
@@ -262,30 +746,27 @@ impl L1BlockInfo {
         // compressed_size(tx) = min(len(zstd(rlp(tx))), len(rlp(tx)))
         // penalty(tx) = compressed_size(tx) / penalty_factor
 
-        assert!(
-            compressed_size <= tx_size,
-            "transaction compressed size {compressed_size} must be less than or equal to the original size {tx_size}"
-        );
+        if compressed_size > tx_size {
+            return Err(L1FeeError::CompressedSizeExceedsTxSize { compressed_size, tx_size });
+        }
 
+        let mut breakdown = L1FeeBreakdown {
+            compressed_size: Some(compressed_size),
+            ..L1FeeBreakdown::new(L1FeeFormula::Galileo, tx_size)
+        };
         let compressed_size = U256::from(compressed_size);
 
-        let exec_scalar = self
-            .l1_commit_scalar
-            .unwrap_or_else(|| panic!("missing exec scalar in spec_id={spec_id:?}"));
-
-        let blob_scalar = self
-            .l1_blob_scalar
-            .unwrap_or_else(|| panic!("missing l1 blob scalar in spec_id={spec_id:?}"));
-
-        let l1_blob_base_fee = self
-            .l1_blob_base_fee
-            .unwrap_or_else(|| panic!("missing l1 blob base fee in spec_id={spec_id:?}"));
+        let exec_scalar =
+            require(self.l1_commit_scalar, L1BlockInfoField::L1CommitScalar, spec_id)?;
+        let blob_scalar = require(self.l1_blob_scalar, L1BlockInfoField::L1BlobScalar, spec_id)?;
+        let l1_blob_base_fee =
+            require(self.l1_blob_base_fee, L1BlockInfoField::L1BlobBaseFee, spec_id)?;
 
-        let penalty_factor = match self.penalty_factor {
-            Some(f) if f == U256::ZERO => U256::ONE, // sanitize zero penalty factor
-            Some(f) => f,
-            None => panic!("missing penalty factor in spec_id={spec_id:?}"),
-        };
+        let penalty_factor =
+            match require(self.penalty_factor, L1BlockInfoField::PenaltyFactor, spec_id)? {
+                f if f == U256::ZERO => U256::ONE, // sanitize zero penalty factor
+                f => f,
+            };
 
         // fee_per_byte = (exec_scalar * l1_base_fee) + (blob_scalar * l1_blob_base_fee)
         let component_exec = exec_scalar.saturating_mul(self.l1_base_fee);
@@ -299,10 +780,22 @@ impl L1BlockInfo {
         let penalty_term = base_term.saturating_mul(compressed_size).wrapping_div(penalty_factor);
 
         // rollup_fee = (base_term + penalty_term) / PRECISION
-        base_term.saturating_add(penalty_term).wrapping_div(TX_L1_FEE_PRECISION_U256)
+        let l1_cost = base_term.saturating_add(penalty_term).wrapping_div(TX_L1_FEE_PRECISION_U256);
+
+        breakdown.component_exec = component_exec;
+        breakdown.component_blob = component_blob;
+        breakdown.fee_per_byte = Some(fee_per_byte);
+        breakdown.penalty =
+            l1_cost.saturating_sub(base_term.wrapping_div(TX_L1_FEE_PRECISION_U256));
+        Ok((l1_cost, breakdown))
     }
 
     /// Calculate the gas cost of a transaction based on L1 block data posted on L2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rollup fee cannot be computed. See [`L1BlockInfo::try_calculate_tx_l1_cost`]
+    /// for a fallible version.
     pub fn calculate_tx_l1_cost(
         &self,
         input: &[u8],
@@ -310,28 +803,65 @@ impl L1BlockInfo {
         compression_ratio: Option<U256>,
         compressed_size: Option<usize>,
     ) -> U256 {
-        let l1_cost = if !spec_id.is_enabled_in(ScrollSpecId::CURIE) {
-            self.calculate_tx_l1_cost_shanghai(input, spec_id)
+        self.try_calculate_tx_l1_cost(input, spec_id, compression_ratio, compressed_size)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Try to calculate the gas cost of a transaction based on L1 block data posted on L2.
+    ///
+    /// Returns an [`L1FeeError`] if a field required by the spec is missing or if the provided
+    /// compression information is invalid.
+    pub fn try_calculate_tx_l1_cost(
+        &self,
+        input: &[u8],
+        spec_id: ScrollSpecId,
+        compression_ratio: Option<U256>,
+        compressed_size: Option<usize>,
+    ) -> Result<U256, L1FeeError> {
+        self.try_calculate_tx_l1_cost_with_breakdown(
+            input,
+            spec_id,
+            compression_ratio,
+            compressed_size,
+        )
+        .map(|(l1_cost, _)| l1_cost)
+    }
+
+    /// Try to calculate the gas cost of a transaction based on L1 block data posted on L2,
+    /// alongside the [`L1FeeBreakdown`] of the fee.
+    pub fn try_calculate_tx_l1_cost_with_breakdown(
+        &self,
+        input: &[u8],
+        spec_id: ScrollSpecId,
+        compression_ratio: Option<U256>,
+        compressed_size: Option<usize>,
+    ) -> Result<(U256, L1FeeBreakdown), L1FeeError> {
+        let (l1_cost, mut breakdown) = if !spec_id.is_enabled_in(ScrollSpecId::CURIE) {
+            self.calculate_tx_l1_cost_shanghai(input, spec_id)?
         } else if !spec_id.is_enabled_in(ScrollSpecId::FEYNMAN) {
-            self.calculate_tx_l1_cost_curie(input, spec_id)
+            self.calculate_tx_l1_cost_curie(input, spec_id)?
         } else if !spec_id.is_enabled_in(ScrollSpecId::GALILEO) {
-            let compression_ratio = compression_ratio.unwrap_or_else(|| {
-                panic!("compression ratio should be set in spec_id={spec_id:?}")
-            });
-            self.calculate_tx_l1_cost_feynman(input, spec_id, compression_ratio)
+            let compression_ratio =
+                compression_ratio.ok_or(L1FeeError::MissingCompressionRatio { spec_id })?;
+            self.calculate_tx_l1_cost_feynman(input, spec_id, compression_ratio)?
         } else {
-            let compressed_size = compressed_size
-                .unwrap_or_else(|| panic!("compressed size should be set in spec_id={spec_id:?}"));
-            self.calculate_tx_l1_cost_galileo(input.len(), spec_id, compressed_size)
+            let compressed_size =
+                compressed_size.ok_or(L1FeeError::MissingCompressedSize { spec_id })?;
+            self.calculate_tx_l1_cost_galileo(input.len(), spec_id, compressed_size)?
         };
-        l1_cost.min(U64_MAX)
+        breakdown.clamped = l1_cost > U64_MAX;
+        Ok((l1_cost.min(U64_MAX), breakdown))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use revm::primitives::uint;
+    use revm::{
+        database::{InMemoryDB, State},
+        primitives::uint,
+        state::AccountInfo,
+    };
     use rstest::rstest;
 
     #[rstest]
@@ -352,7 +882,400 @@ mod tests {
 
         let tx_size = 1e10 as usize; // dummy, but make sure this value is larger than the compressed size
         let spec = ScrollSpecId::GALILEO;
-        let actual = gpo.calculate_tx_l1_cost_galileo(tx_size, spec, compressed_size);
+        let (actual, _) = gpo.calculate_tx_l1_cost_galileo(tx_size, spec, compressed_size).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_data_gas_missing_field() {
+        let gpo = L1BlockInfo { l1_blob_base_fee: Some(U256::ONE), ..Default::default() };
+
+        assert!(gpo.data_gas(&[1u8; 10], ScrollSpecId::BERNOULLI).is_ok());
+        assert_eq!(
+            gpo.data_gas(&[1u8; 10], ScrollSpecId::CURIE),
+            Err(L1FeeError::MissingField {
+                field: L1BlockInfoField::L1BlobScalar,
+                spec_id: ScrollSpecId::CURIE
+            })
+        );
+    }
+
+    #[test]
+    fn test_rollup_fee_missing_field() {
+        let gpo = L1BlockInfo { l1_base_fee: uint!(1_000_000_000_U256), ..Default::default() };
+
+        let err =
+            gpo.try_calculate_tx_l1_cost(&[1u8; 10], ScrollSpecId::CURIE, None, None).unwrap_err();
+        assert_eq!(
+            err,
+            L1FeeError::MissingField {
+                field: L1BlockInfoField::CalldataGas,
+                spec_id: ScrollSpecId::CURIE
+            }
+        );
+
+        let err = gpo
+            .try_calculate_tx_l1_cost(&[1u8; 10], ScrollSpecId::GALILEO, None, None)
+            .unwrap_err();
+        assert_eq!(err, L1FeeError::MissingCompressedSize { spec_id: ScrollSpecId::GALILEO });
+    }
+
+    #[test]
+    fn test_validate() {
+        let findings = L1BlockInfo::default().validate(ScrollSpecId::BERNOULLI);
+        assert_eq!(
+            findings,
+            [L1BlockInfoFinding::ZeroScalar { field: L1BlockInfoField::L1BaseFeeScalar }]
+        );
+
+        let gpo = L1BlockInfo {
+            l1_base_fee: U256::MAX,
+            l1_base_fee_scalar: U256::from(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            gpo.validate(ScrollSpecId::BERNOULLI),
+            [L1BlockInfoFinding::Overflow {
+                lhs: L1BlockInfoField::L1BaseFee,
+                rhs: L1BlockInfoField::L1BaseFeeScalar
+            }]
+        );
+
+        let findings = L1BlockInfo::default().validate(ScrollSpecId::CURIE);
+        assert_eq!(
+            findings,
+            [
+                L1BlockInfoField::L1BlobBaseFee,
+                L1BlockInfoField::L1CommitScalar,
+                L1BlockInfoField::L1BlobScalar,
+                L1BlockInfoField::CalldataGas
+            ]
+            .map(|field| L1BlockInfoFinding::MissingField { field })
+        );
+
+        let gpo = L1BlockInfo {
+            l1_base_fee: uint!(1_000_000_000_U256),
+            l1_blob_base_fee: Some(uint!(1_000_000_000_U256)),
+            l1_commit_scalar: Some(U256::ZERO),
+            l1_blob_scalar: Some(uint!(1019097245_U256)),
+            calldata_gas: Some(U256::ZERO),
+            penalty_threshold: Some(uint!(100_000_000_U256)),
+            penalty_factor: Some(U256::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(
+            gpo.validate(ScrollSpecId::CURIE),
+            [L1BlockInfoFinding::ZeroScalar { field: L1BlockInfoField::L1CommitScalar }]
+        );
+        assert_eq!(
+            gpo.validate(ScrollSpecId::FEYNMAN),
+            [
+                L1BlockInfoFinding::ZeroScalar { field: L1BlockInfoField::L1CommitScalar },
+                L1BlockInfoFinding::PenaltyOutOfRange {
+                    field: L1BlockInfoField::PenaltyThreshold,
+                    value: uint!(100_000_000_U256)
+                },
+                L1BlockInfoFinding::PenaltyOutOfRange {
+                    field: L1BlockInfoField::PenaltyFactor,
+                    value: U256::ZERO
+                },
+            ]
+        );
+        assert_eq!(
+            gpo.validate(ScrollSpecId::GALILEO),
+            [
+                L1BlockInfoFinding::ZeroScalar { field: L1BlockInfoField::L1CommitScalar },
+                L1BlockInfoFinding::PenaltyOutOfRange {
+                    field: L1BlockInfoField::PenaltyFactor,
+                    value: U256::ZERO
+                },
+            ]
+        );
+
+        let gpo = L1BlockInfo {
+            l1_base_fee: U256::MAX,
+            l1_blob_base_fee: Some(U256::ONE),
+            l1_commit_scalar: Some(U256::from(2)),
+            l1_blob_scalar: Some(U256::ONE),
+            calldata_gas: Some(U256::ONE),
+            ..Default::default()
+        };
+        assert_eq!(
+            gpo.validate(ScrollSpecId::CURIE),
+            [L1BlockInfoFinding::Overflow {
+                lhs: L1BlockInfoField::L1CommitScalar,
+                rhs: L1BlockInfoField::L1BaseFee
+            }]
+        );
+
+        let gpo = L1BlockInfo {
+            l1_blob_base_fee: Some(U256::MAX),
+            l1_commit_scalar: Some(U256::ONE),
+            l1_blob_scalar: Some(U256::from(2)),
+            penalty_factor: Some(U256::ONE),
+            ..Default::default()
+        };
+        assert_eq!(
+            gpo.validate(ScrollSpecId::GALILEO),
+            [L1BlockInfoFinding::Overflow {
+                lhs: L1BlockInfoField::L1BlobScalar,
+                rhs: L1BlockInfoField::L1BlobBaseFee
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rollup_fee_invalid_compression() {
+        let gpo = L1BlockInfo {
+            l1_blob_base_fee: Some(U256::ONE),
+            l1_commit_scalar: Some(U256::ONE),
+            l1_blob_scalar: Some(U256::ONE),
+            calldata_gas: Some(U256::ONE),
+            penalty_threshold: Some(U256::ONE),
+            penalty_factor: Some(U256::ONE),
+            ..Default::default()
+        };
+
+        let compression_ratio = U256::from(TX_L1_FEE_PRECISION - 1);
+        let err = gpo
+            .try_calculate_tx_l1_cost(
+                &[1u8; 10],
+                ScrollSpecId::FEYNMAN,
+                Some(compression_ratio),
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(err, L1FeeError::CompressionRatioBelowPrecision { compression_ratio });
+
+        let err = gpo
+            .try_calculate_tx_l1_cost(&[1u8; 10], ScrollSpecId::GALILEO, None, Some(11))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            L1FeeError::CompressedSizeExceedsTxSize { compressed_size: 11, tx_size: 10 }
+        );
+    }
+
+    #[test]
+    fn test_rollup_fee_breakdown_shanghai() {
+        let gpo = L1BlockInfo {
+            l1_base_fee: uint!(10_000_U256),
+            l1_fee_overhead: uint!(10_000_U256),
+            l1_base_fee_scalar: uint!(10_000_U256),
+            ..Default::default()
+        };
+
+        let (fee, breakdown) = gpo
+            .try_calculate_tx_l1_cost_with_breakdown(&[1u8; 4], ScrollSpecId::BERNOULLI, None, None)
+            .unwrap();
+
+        // data gas is 4 * 16 + 10_000 + 64 = 10_128.
+        assert_eq!(fee, uint!(1012_U256));
+        assert_eq!(
+            breakdown,
+            L1FeeBreakdown {
+                formula: L1FeeFormula::Shanghai,
+                component_exec: uint!(1_012_800_000_000_U256),
+                component_blob: U256::ZERO,
+                fee_per_byte: None,
+                penalty: U256::ZERO,
+                tx_size: 4,
+                compressed_size: None,
+                clamped: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rollup_fee_breakdown_curie() {
+        let gpo = L1BlockInfo {
+            l1_base_fee: uint!(10_000_U256),
+            l1_blob_base_fee: Some(uint!(10_000_U256)),
+            l1_commit_scalar: Some(uint!(10_000_U256)),
+            l1_blob_scalar: Some(uint!(1_000_000_000_U256)),
+            calldata_gas: Some(uint!(100_000_000_U256)),
+            ..Default::default()
+        };
+
+        let (fee, breakdown) = gpo
+            .try_calculate_tx_l1_cost_with_breakdown(&[1u8; 4], ScrollSpecId::CURIE, None, None)
+            .unwrap();
+
+        assert_eq!(fee, uint!(40_000_U256));
+        assert_eq!(breakdown.formula, L1FeeFormula::Curie);
+        assert_eq!(breakdown.component_exec, uint!(100_000_000_U256));
+        assert_eq!(breakdown.component_blob, uint!(40_000_000_000_000_U256));
+        assert_eq!(breakdown.fee_per_byte, None);
+        assert_eq!(breakdown.penalty, U256::ZERO);
+    }
+
+    #[rstest]
+    #[case(uint!(5_000_000_000_U256), uint!(6000_U256), uint!(3000_U256))] // penalized
+    #[case(uint!(6_000_000_000_U256), uint!(3000_U256), U256::ZERO)] // not penalized
+    fn test_rollup_fee_breakdown_feynman(
+        #[case] compression_ratio: U256,
+        #[case] expected_fee: U256,
+        #[case] expected_penalty: U256,
+    ) {
+        let gpo = L1BlockInfo {
+            l1_base_fee: uint!(1_000_000_000_U256),
+            l1_blob_base_fee: Some(uint!(1_000_000_000_U256)),
+            l1_commit_scalar: Some(uint!(10_U256)),
+            l1_blob_scalar: Some(uint!(20_U256)),
+            penalty_threshold: Some(uint!(6_000_000_000_U256)),
+            penalty_factor: Some(uint!(2_000_000_000_U256)),
+            ..Default::default()
+        };
+
+        let (fee, breakdown) = gpo
+            .try_calculate_tx_l1_cost_with_breakdown(
+                &[0u8; 100],
+                ScrollSpecId::FEYNMAN,
+                Some(compression_ratio),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(fee, expected_fee);
+        assert_eq!(breakdown.formula, L1FeeFormula::Feynman);
+        assert_eq!(breakdown.component_exec, uint!(10_000_000_000_U256));
+        assert_eq!(breakdown.component_blob, uint!(20_000_000_000_U256));
+        assert_eq!(breakdown.fee_per_byte, Some(uint!(30_000_000_000_U256)));
+        assert_eq!(breakdown.penalty, expected_penalty);
+        assert_eq!(breakdown.tx_size, 100);
+    }
+
+    #[test]
+    fn test_rollup_fee_breakdown_galileo() {
+        let gpo = L1BlockInfo {
+            l1_base_fee: uint!(1_000_000_000_U256),
+            l1_blob_base_fee: Some(uint!(1_000_000_000_U256)),
+            l1_commit_scalar: Some(uint!(2394981796_U256)),
+            l1_blob_scalar: Some(uint!(1019097245_U256)),
+            penalty_factor: Some(uint!(10000_U256)),
+            ..Default::default()
+        };
+
+        let (fee, breakdown) = gpo
+            .try_calculate_tx_l1_cost_with_breakdown(
+                &[1u8; 100],
+                ScrollSpecId::GALILEO,
+                None,
+                Some(50),
+            )
+            .unwrap();
+
+        assert_eq!(fee, uint!(171557471810_U256));
+        assert_eq!(
+            breakdown,
+            L1FeeBreakdown {
+                formula: L1FeeFormula::Galileo,
+                component_exec: uint!(2394981796000000000_U256),
+                component_blob: uint!(1019097245000000000_U256),
+                fee_per_byte: Some(uint!(3414079041000000000_U256)),
+                penalty: uint!(853519760_U256),
+                tx_size: 100,
+                compressed_size: Some(50),
+                clamped: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rollup_fee_breakdown_clamped() {
+        let gpo = L1BlockInfo {
+            l1_base_fee: U256::MAX,
+            l1_base_fee_scalar: U256::ONE,
+            ..Default::default()
+        };
+
+        let (fee, breakdown) = gpo
+            .try_calculate_tx_l1_cost_with_breakdown(&[1u8; 4], ScrollSpecId::SHANGHAI, None, None)
+            .unwrap();
+
+        assert_eq!(fee, U64_MAX);
+        assert!(breakdown.clamped);
+    }
+
+    #[rstest]
+    #[case(ScrollSpecId::BERNOULLI)]
+    #[case(ScrollSpecId::CURIE)]
+    #[case(ScrollSpecId::FEYNMAN)]
+    #[case(ScrollSpecId::GALILEO)]
+    fn test_write_l1_block_info(#[case] spec: ScrollSpecId) {
+        let info = L1BlockInfo {
+            l1_base_fee: uint!(1_000_000_000_U256),
+            l1_fee_overhead: uint!(2_500_U256),
+            l1_base_fee_scalar: uint!(1_150_000_000_U256),
+            l1_blob_base_fee: Some(uint!(2_000_000_000_U256)),
+            l1_commit_scalar: Some(uint!(2394981796_U256)),
+            l1_blob_scalar: Some(uint!(1019097245_U256)),
+            calldata_gas: Some(uint!(2394981796000000000_U256)),
+            penalty_threshold: Some(uint!(6_000_000_000_U256)),
+            penalty_factor: Some(uint!(10000_U256)),
+        };
+        let expected = if !spec.is_enabled_in(ScrollSpecId::CURIE) {
+            L1BlockInfo {
+                l1_base_fee: info.l1_base_fee,
+                l1_fee_overhead: info.l1_fee_overhead,
+                l1_base_fee_scalar: info.l1_base_fee_scalar,
+                ..Default::default()
+            }
+        } else if !spec.is_enabled_in(ScrollSpecId::FEYNMAN) {
+            L1BlockInfo { penalty_threshold: None, penalty_factor: None, ..info.clone() }
+        } else {
+            info.clone()
+        };
+
+        let mut db = InMemoryDB::default();
+        info.write_to(&mut db, spec).unwrap();
+
+        assert_eq!(L1BlockInfo::try_fetch(&mut db, spec).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_write_l1_block_info_state() {
+        let info = L1BlockInfo {
+            l1_base_fee: uint!(1_000_000_000_U256),
+            l1_fee_overhead: uint!(2_500_U256),
+            l1_base_fee_scalar: uint!(1_150_000_000_U256),
+            l1_blob_base_fee: Some(uint!(2_000_000_000_U256)),
+            l1_commit_scalar: Some(uint!(2394981796_U256)),
+            l1_blob_scalar: Some(uint!(1019097245_U256)),
+            ..Default::default()
+        };
+
+        // the deployed oracle is not an empty account, which state clearing would remove.
+        let mut inner = InMemoryDB::default();
+        inner.insert_account_info(
+            L1_GAS_PRICE_ORACLE_ADDRESS,
+            AccountInfo { nonce: 1, ..Default::default() },
+        );
+        L1BlockInfo { l1_base_fee: uint!(1_U256), ..info.clone() }
+            .write_to(&mut inner, ScrollSpecId::CURIE)
+            .unwrap();
+
+        // State only persists the changed slots of committed accounts.
+        let mut state = State::builder().with_database(inner).build();
+        info.write_to(&mut state, ScrollSpecId::CURIE).unwrap();
+
+        assert_eq!(L1BlockInfo::try_fetch(&mut state, ScrollSpecId::CURIE).unwrap(), info);
+        let oracle = &state.cache.accounts[&L1_GAS_PRICE_ORACLE_ADDRESS];
+        let storage = &oracle.account.as_ref().unwrap().storage;
+        assert_eq!(storage[&L1_BASE_FEE_SLOT], info.l1_base_fee);
+    }
+
+    #[test]
+    fn test_storage_entries_missing_field() {
+        let info = L1BlockInfo::default();
+
+        assert_eq!(info.storage_entries(ScrollSpecId::BERNOULLI).unwrap().len(), 3);
+        assert_eq!(
+            info.storage_entries(ScrollSpecId::CURIE).unwrap_err(),
+            L1FeeError::MissingField {
+                field: L1BlockInfoField::L1BlobBaseFee,
+                spec_id: ScrollSpecId::CURIE
+            }
+        );
+    }
 }
@@ -4,21 +4,56 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc as std;
 
+pub mod alias;
+
+pub mod base_fee;
+
+pub mod block;
+
 pub mod builder;
 
+pub use chain::{ScrollChainContext, ScrollChainTr, ScrollFeeRouting, ScrollTxFees};
+pub mod chain;
+
+#[cfg(feature = "compression")]
+pub mod compression;
+
+pub mod da_cost;
+
+pub mod envelope;
+
+pub use error::ScrollInvalidTransaction;
+pub mod error;
+
+pub mod estimate;
+
 pub use evm::ScrollEvm;
 pub mod evm;
 
 mod exec;
 
+pub use executor::ScrollBlockExecutor;
+pub mod executor;
+
+pub mod fee_conservation;
+
 pub mod handler;
 
 pub mod instructions;
 
 pub mod l1block;
 
+pub mod message_queue;
+
+pub mod oracle_consistency;
+
 pub mod precompile;
 
+pub mod quote;
+
+pub use rollup_fee::{RollupFeeModel, ScrollRollupFeeModel};
+pub mod rollup_fee;
+
 pub use spec::*;
 mod spec;
 
@@ -28,7 +63,9 @@ mod tests;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-pub use transaction::ScrollTransaction;
+pub use transaction::{ScrollTransaction, ScrollTxTr, TxL1Message};
 mod transaction;
 
 pub mod system_call;
+
+pub mod validation;
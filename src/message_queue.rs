@@ -0,0 +1,203 @@
+//! L1 message queue hash.
+//!
+//! From EuclidV2, the L1 message queue commits to the enqueued L1 messages with a rolling hash:
+//!
+//! `queue_hash = keccak256(queue_hash || message_hash)` with the last 32 bits cleared
+//!
+//! where `message_hash` is the keccak256 of the EIP-2718 encoding of the [`TxL1Message`]. The
+//! L1 messages included in a block must advance the queue hash of the parent block to the queue
+//! hash of the block.
+
+use crate::transaction::{ScrollTransaction, ScrollTxTr, TxL1Message};
+use core::fmt;
+
+use revm::{
+    context::Transaction,
+    primitives::{Keccak256, B256},
+};
+
+/// Returns the queue hash after appending the message hash to the queue.
+pub fn next_queue_hash(queue_hash: B256, message_hash: B256) -> B256 {
+    let mut hasher = Keccak256::new();
+    hasher.update(queue_hash);
+    hasher.update(message_hash);
+    let mut queue_hash = hasher.finalize();
+    queue_hash.0[28..].fill(0);
+    queue_hash
+}
+
+/// An error that occurred while computing or verifying the L1 message queue hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum L1MessageQueueError {
+    /// The L1 message at the index of the transactions is not a valid [`TxL1Message`], i.e. it
+    /// has no queue index or creates a contract.
+    InvalidL1Message {
+        /// The index of the transaction.
+        index: usize,
+    },
+    /// The queue hash does not match the L1 message queue.
+    QueueHashMismatch {
+        /// The queue index of the L1 message after which the queue hash does not match, None if
+        /// the transactions do not include L1 messages.
+        queue_index: Option<u64>,
+        /// The expected queue hash.
+        expected: B256,
+        /// The computed queue hash.
+        got: B256,
+    },
+}
+
+impl core::error::Error for L1MessageQueueError {}
+
+impl fmt::Display for L1MessageQueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidL1Message { index } => write!(f, "invalid L1 message at index {index}"),
+            Self::QueueHashMismatch { queue_index: Some(queue_index), expected, got } => write!(
+                f,
+                "L1 message queue hash mismatch after queue index {queue_index}: expected {expected}, got {got}"
+            ),
+            Self::QueueHashMismatch { queue_index: None, expected, got } => {
+                write!(f, "L1 message queue hash mismatch: expected {expected}, got {got}")
+            }
+        }
+    }
+}
+
+/// Computes the queue hash after the L1 messages of the transactions, starting from the queue
+/// hash of the parent block. Transactions which are not L1 messages are skipped.
+pub fn compute_queue_hash<'a, T: Transaction + 'a>(
+    parent_queue_hash: B256,
+    txs: impl IntoIterator<Item = &'a ScrollTransaction<T>>,
+) -> Result<B256, L1MessageQueueError> {
+    verify_queue_hash_with(parent_queue_hash, txs, |_| None).map(|(queue_hash, _)| queue_hash)
+}
+
+/// Verifies the L1 messages of the transactions advance the queue hash of the parent block to
+/// the expected queue hash of the block.
+///
+/// `expected_at` returns the queue hash of the L1 message queue after the message with the queue
+/// index if known, e.g. from the rolling hashes stored by the L1 message queue contract, such that
+/// the first mismatching L1 message is reported. Otherwise, a mismatch is reported for the last
+/// L1 message of the transactions.
+pub fn verify_queue_hash<'a, T: Transaction + 'a>(
+    parent_queue_hash: B256,
+    txs: impl IntoIterator<Item = &'a ScrollTransaction<T>>,
+    expected_queue_hash: B256,
+    expected_at: impl FnMut(u64) -> Option<B256>,
+) -> Result<(), L1MessageQueueError> {
+    let (got, last_queue_index) = verify_queue_hash_with(parent_queue_hash, txs, expected_at)?;
+    if got != expected_queue_hash {
+        return Err(L1MessageQueueError::QueueHashMismatch {
+            queue_index: last_queue_index,
+            expected: expected_queue_hash,
+            got,
+        });
+    }
+    Ok(())
+}
+
+/// Computes the queue hash after the L1 messages of the transactions, checking the intermediate
+/// queue hashes returned by `expected_at`. Returns the queue hash and the queue index of the last
+/// L1 message.
+fn verify_queue_hash_with<'a, T: Transaction + 'a>(
+    parent_queue_hash: B256,
+    txs: impl IntoIterator<Item = &'a ScrollTransaction<T>>,
+    mut expected_at: impl FnMut(u64) -> Option<B256>,
+) -> Result<(B256, Option<u64>), L1MessageQueueError> {
+    let mut queue_hash = parent_queue_hash;
+    let mut last_queue_index = None;
+    for (index, tx) in txs.into_iter().enumerate().filter(|(_, tx)| tx.is_l1_msg()) {
+        let message: TxL1Message =
+            tx.l1_message().ok_or(L1MessageQueueError::InvalidL1Message { index })?;
+        queue_hash = next_queue_hash(queue_hash, message.tx_hash());
+        last_queue_index = Some(message.queue_index);
+
+        if let Some(expected) = expected_at(message.queue_index) {
+            if expected != queue_hash {
+                return Err(L1MessageQueueError::QueueHashMismatch {
+                    queue_index: Some(message.queue_index),
+                    expected,
+                    got: queue_hash,
+                });
+            }
+        }
+    }
+    Ok((queue_hash, last_queue_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        context::TxEnv,
+        primitives::{address, b256, Bytes, U256},
+    };
+    use std::vec::Vec;
+
+    fn l1_message(queue_index: u64) -> ScrollTransaction<TxEnv> {
+        TxL1Message {
+            queue_index,
+            gas_limit: 100_000,
+            to: address!("0x00000000000000000000000000000000000dead1"),
+            value: U256::from(10u64.pow(18)),
+            sender: address!("0x000000000000000000000000000000000000dead"),
+            input: Bytes::from_static(&[1, 2, 3, 4]),
+        }
+        .into()
+    }
+
+    const QUEUE_HASH_1: B256 =
+        b256!("0x47a057ed597d05fda318f0c4dc5c7171150ab9e6df95ddac1080d03000000000");
+    const QUEUE_HASH_2: B256 =
+        b256!("0xd31285073e26464ce33cff33ae9119f4ad9eb057d504cb73f6925c8600000000");
+
+    #[test]
+    fn test_compute_queue_hash() {
+        let txs = [l1_message(0), ScrollTransaction::default(), l1_message(1)];
+
+        assert_eq!(compute_queue_hash(B256::ZERO, &txs[..1]), Ok(QUEUE_HASH_1));
+        assert_eq!(compute_queue_hash(QUEUE_HASH_1, &txs[1..]), Ok(QUEUE_HASH_2));
+        assert_eq!(compute_queue_hash(B256::ZERO, &txs), Ok(QUEUE_HASH_2));
+        // transactions which are not L1 messages leave the queue hash unchanged.
+        assert_eq!(compute_queue_hash(QUEUE_HASH_1, &txs[1..2]), Ok(QUEUE_HASH_1));
+    }
+
+    #[test]
+    fn test_verify_queue_hash() {
+        let txs = Vec::from([l1_message(0), l1_message(1)]);
+        let expected_at =
+            |queue_index: u64| [QUEUE_HASH_1, QUEUE_HASH_2].get(queue_index as usize).copied();
+
+        assert_eq!(verify_queue_hash(B256::ZERO, &txs, QUEUE_HASH_2, expected_at), Ok(()));
+        assert_eq!(
+            verify_queue_hash(B256::ZERO, &txs, QUEUE_HASH_1, |_| None),
+            Err(L1MessageQueueError::QueueHashMismatch {
+                queue_index: Some(1),
+                expected: QUEUE_HASH_1,
+                got: QUEUE_HASH_2
+            })
+        );
+
+        // the first mismatching L1 message is reported.
+        let mut tampered = txs.clone();
+        tampered[0].base.value = U256::ZERO;
+        let err = verify_queue_hash(B256::ZERO, &tampered, QUEUE_HASH_2, expected_at).unwrap_err();
+        assert!(matches!(
+            err,
+            L1MessageQueueError::QueueHashMismatch {
+                queue_index: Some(0),
+                expected: QUEUE_HASH_1,
+                ..
+            }
+        ));
+
+        // L1 messages must have a queue index.
+        let mut invalid = txs;
+        invalid[1].queue_index = None;
+        assert_eq!(
+            verify_queue_hash(B256::ZERO, &invalid, QUEUE_HASH_2, |_| None),
+            Err(L1MessageQueueError::InvalidL1Message { index: 1 })
+        );
+    }
+}
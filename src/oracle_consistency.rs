@@ -0,0 +1,304 @@
+//! Consistency checks between the native rollup fee and the L1 gas price oracle.
+//!
+//! Users query the rollup fee of a transaction by calling `getL1Fee(bytes)` on the
+//! [`L1_GAS_PRICE_ORACLE_ADDRESS`] contract, while the node charges the fee computed by
+//! [`L1BlockInfo::try_calculate_tx_l1_cost`]. Any drift between both is a consensus visible bug.
+
+use crate::{
+    builder::{DefaultScrollContext, ScrollBuilder, ScrollContext},
+    error::ScrollInvalidTransaction,
+    l1block::{L1BlockInfo, L1FeeError, L1_GAS_PRICE_ORACLE_ADDRESS},
+    ScrollSpecId,
+};
+use std::vec::Vec;
+
+use revm::{
+    context::{result::ExecutionResult, BlockEnv, ContextTr},
+    context_interface::result::EVMError,
+    primitives::{Bytes, U256},
+    Context, Database, SystemCallEvm,
+};
+
+/// The selector of `getL1Fee(bytes)`.
+pub const GET_L1_FEE_SELECTOR: [u8; 4] = [0x49, 0x94, 0x8e, 0x0e];
+
+/// A payload for which the rollup fee is checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct L1FeeSample {
+    /// The RLP encoded transaction.
+    pub payload: Bytes,
+    /// The compression ratio of the transaction, required from FEYNMAN.
+    pub compression_ratio: Option<U256>,
+    /// The compressed size of the transaction, required from GALILEO.
+    pub compressed_size: Option<usize>,
+}
+
+impl L1FeeSample {
+    /// Returns a new [`L1FeeSample`].
+    pub fn new(
+        payload: Bytes,
+        compression_ratio: Option<U256>,
+        compressed_size: Option<usize>,
+    ) -> Self {
+        Self { payload, compression_ratio, compressed_size }
+    }
+}
+
+/// A sample for which the oracle and the native rollup fee differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L1FeeMismatch {
+    /// The index of the sample in the corpus.
+    pub index: usize,
+    /// The fee returned by the oracle, None if the call did not return a single word.
+    pub oracle_fee: Option<U256>,
+    /// The fee computed natively.
+    pub native_fee: Result<U256, L1FeeError>,
+}
+
+/// The result of checking a corpus of samples for a spec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L1FeeConsistencyReport {
+    /// The spec the corpus was checked for.
+    pub spec: ScrollSpecId,
+    /// The number of samples checked.
+    pub checked: usize,
+    /// The samples for which the oracle and the native rollup fee differ.
+    pub mismatches: Vec<L1FeeMismatch>,
+}
+
+impl L1FeeConsistencyReport {
+    /// Returns true if the oracle agrees with the native rollup fee for all samples.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Returns the ABI encoded call to `getL1Fee(bytes)` for the payload.
+pub fn encode_get_l1_fee(payload: &[u8]) -> Bytes {
+    let padded_len = payload.len().div_ceil(32) * 32;
+    let mut data = Vec::with_capacity(4 + 64 + padded_len);
+    data.extend_from_slice(&GET_L1_FEE_SELECTOR);
+    data.extend_from_slice(&U256::from(32).to_be_bytes::<32>());
+    data.extend_from_slice(&U256::from(payload.len()).to_be_bytes::<32>());
+    data.extend_from_slice(payload);
+    data.resize(4 + 64 + padded_len, 0);
+    data.into()
+}
+
+/// Calls `getL1Fee(bytes)` on the oracle deployed in the database for each sample and compares
+/// the result with the rollup fee computed natively from the oracle storage for the spec.
+///
+/// Calls are executed as system calls in the provided block and are never committed. Every
+/// mismatching sample is reported.
+pub fn check_l1_fee_consistency<DB: Database>(
+    db: DB,
+    spec: ScrollSpecId,
+    block: BlockEnv,
+    samples: &[L1FeeSample],
+) -> Result<L1FeeConsistencyReport, EVMError<DB::Error, ScrollInvalidTransaction>> {
+    let ctx: ScrollContext<DB> =
+        Context::scroll().with_db(db).with_block(block).modify_cfg_chained(|cfg| cfg.spec = spec);
+    let mut evm = ctx.build_scroll();
+
+    let l1_block_info =
+        L1BlockInfo::try_fetch(evm.0.ctx.db_mut(), spec).map_err(EVMError::Database)?;
+
+    let mut mismatches = Vec::new();
+    for (index, sample) in samples.iter().enumerate() {
+        let native_fee = l1_block_info.try_calculate_tx_l1_cost(
+            &sample.payload,
+            spec,
+            sample.compression_ratio,
+            sample.compressed_size,
+        );
+
+        let result =
+            evm.system_call_one(L1_GAS_PRICE_ORACLE_ADDRESS, encode_get_l1_fee(&sample.payload))?;
+        let oracle_fee = match result {
+            ExecutionResult::Success { output, .. } if output.data().len() == 32 => {
+                Some(U256::from_be_slice(output.data()))
+            }
+            _ => None,
+        };
+
+        if oracle_fee.is_none() || oracle_fee != native_fee.as_ref().ok().copied() {
+            mismatches.push(L1FeeMismatch { index, oracle_fee, native_fee });
+        }
+    }
+
+    Ok(L1FeeConsistencyReport { spec, checked: samples.len(), mismatches })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{l1_block_info, TX_L1_FEE_PRECISION};
+    use revm::{
+        database::InMemoryDB,
+        primitives::{bytes, hex},
+        state::{AccountInfo, Bytecode},
+    };
+    use rstest::rstest;
+
+    /// A minimal oracle implementing the CURIE `getL1Fee(bytes)` formula:
+    /// `(commitScalar * l1BaseFee + blobScalar * _data.length * l1BlobBaseFee) / PRECISION`.
+    ///
+    /// This fixture only exercises the checker, the consistency of each fork is checked against
+    /// the deployed oracle code in [`test_deployed_oracle`].
+    const CURIE_ORACLE: Bytes =
+        bytes!("60243560055402600754026006546001540201633b9aca00900460005260206000f3");
+
+    fn oracle_db(code: Bytes) -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        l1_block_info()
+            .write_to(&mut db, ScrollSpecId::GALILEO)
+            .expect("the test L1 block info should be complete");
+        let code = Bytecode::new_raw(code);
+        db.insert_account_info(
+            L1_GAS_PRICE_ORACLE_ADDRESS,
+            AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+        );
+        db
+    }
+
+    fn corpus() -> Vec<L1FeeSample> {
+        [Bytes::new(), bytes!("01"), bytes!("00000000"), Bytes::from(hex!("02f8b1").repeat(100))]
+            .into_iter()
+            .map(|payload| L1FeeSample::new(payload, None, None))
+            .collect()
+    }
+
+    /// Returns the corpus with the compression information required by the spec. The oracle
+    /// cannot compress the payload, the payloads are therefore treated as incompressible.
+    fn fork_corpus(spec: ScrollSpecId) -> Vec<L1FeeSample> {
+        corpus()
+            .into_iter()
+            .map(|mut sample| {
+                if spec.is_enabled_in(ScrollSpecId::FEYNMAN) {
+                    sample.compression_ratio = Some(TX_L1_FEE_PRECISION);
+                }
+                if spec.is_enabled_in(ScrollSpecId::GALILEO) {
+                    sample.compressed_size = Some(sample.payload.len());
+                }
+                sample
+            })
+            .collect()
+    }
+
+    /// Returns the vendored runtime bytecode of the `L1GasPriceOracle` for the fork, see
+    /// `testdata/l1_gas_price_oracle/README.md`.
+    #[cfg(feature = "std")]
+    fn deployed_oracle(fork: &str) -> Bytes {
+        let path =
+            format!("{}/testdata/l1_gas_price_oracle/{fork}.hex", env!("CARGO_MANIFEST_DIR"));
+        let contents = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("failed to read the oracle code at {path}: {err}"));
+        let code: String =
+            contents.lines().filter(|line| !line.trim_start().starts_with('#')).collect();
+        hex::decode(code.trim()).expect("the oracle code should be hex encoded").into()
+    }
+
+    #[rstest]
+    #[case("bernoulli", ScrollSpecId::BERNOULLI)]
+    #[case("curie", ScrollSpecId::CURIE)]
+    #[case("feynman", ScrollSpecId::FEYNMAN)]
+    #[case("galileo", ScrollSpecId::GALILEO)]
+    #[cfg(feature = "std")]
+    #[ignore = "requires the vendored oracle code, see testdata/l1_gas_price_oracle/README.md"]
+    fn test_deployed_oracle(#[case] fork: &str, #[case] spec: ScrollSpecId) {
+        let report = check_l1_fee_consistency(
+            oracle_db(deployed_oracle(fork)),
+            spec,
+            BlockEnv::default(),
+            &fork_corpus(spec),
+        )
+        .unwrap();
+
+        assert_eq!(report.checked, 4);
+        assert!(report.is_consistent(), "{fork}: {:?}", report.mismatches);
+    }
+
+    #[test]
+    fn test_encode_get_l1_fee() {
+        let data = encode_get_l1_fee(&[0xab; 33]);
+        assert_eq!(data.len(), 4 + 32 + 32 + 64);
+        assert_eq!(data[..4], GET_L1_FEE_SELECTOR);
+        assert_eq!(U256::from_be_slice(&data[4..36]), U256::from(32));
+        assert_eq!(U256::from_be_slice(&data[36..68]), U256::from(33));
+        assert_eq!(data[68..101], [0xab; 33]);
+        assert!(data[101..].iter().all(|byte| *byte == 0));
+    }
+
+    #[rstest]
+    #[case(ScrollSpecId::CURIE)]
+    #[case(ScrollSpecId::DARWIN)]
+    #[case(ScrollSpecId::EUCLID)]
+    fn test_consistent_oracle(#[case] spec: ScrollSpecId) {
+        let report =
+            check_l1_fee_consistency(oracle_db(CURIE_ORACLE), spec, BlockEnv::default(), &corpus())
+                .unwrap();
+
+        assert_eq!(report.spec, spec);
+        assert_eq!(report.checked, 4);
+        assert!(report.is_consistent(), "{:?}", report.mismatches);
+    }
+
+    #[test]
+    fn test_report_mismatches() {
+        let samples = corpus();
+        let report = check_l1_fee_consistency(
+            oracle_db(CURIE_ORACLE),
+            ScrollSpecId::SHANGHAI,
+            BlockEnv::default(),
+            &samples,
+        )
+        .unwrap();
+
+        // the CURIE oracle does not implement the SHANGHAI formula.
+        assert_eq!(report.mismatches.len(), samples.len());
+        let l1_block_info = l1_block_info();
+        for (index, mismatch) in report.mismatches.iter().enumerate() {
+            assert_eq!(mismatch.index, index);
+            assert_eq!(
+                mismatch.native_fee,
+                l1_block_info.try_calculate_tx_l1_cost(
+                    &samples[index].payload,
+                    ScrollSpecId::SHANGHAI,
+                    None,
+                    None
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_report_failed_oracle_call() {
+        // REVERT(0, 0)
+        let report = check_l1_fee_consistency(
+            oracle_db(bytes!("60006000fd")),
+            ScrollSpecId::CURIE,
+            BlockEnv::default(),
+            &corpus(),
+        )
+        .unwrap();
+
+        assert_eq!(report.mismatches.len(), 4);
+        assert!(report.mismatches.iter().all(|mismatch| mismatch.oracle_fee.is_none()));
+    }
+
+    #[test]
+    fn test_missing_compression_info() {
+        let report = check_l1_fee_consistency(
+            oracle_db(CURIE_ORACLE),
+            ScrollSpecId::FEYNMAN,
+            BlockEnv::default(),
+            &corpus()[..1],
+        )
+        .unwrap();
+
+        assert_eq!(
+            report.mismatches[0].native_fee,
+            Err(L1FeeError::MissingCompressionRatio { spec_id: ScrollSpecId::FEYNMAN })
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Worst-case cost of a transaction.
+//!
+//! The caller of a transaction is charged at most `gas_limit * max_fee_per_gas + value + l1_fee`.
+//! [`quote_max_cost`] computes this cost for a block from the database without executing the
+//! transaction.
+
+use crate::{
+    error::ScrollInvalidTransaction,
+    rollup_fee::{tx_rollup_fee, RollupFeeModel},
+    transaction::ScrollTxTr,
+    ScrollSpecId,
+};
+
+use revm::{
+    context::{
+        result::{EVMError, InvalidTransaction},
+        Block,
+    },
+    primitives::U256,
+    Database,
+};
+
+/// The itemized worst-case cost of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollMaxCost {
+    /// The maximum execution fee, `gas_limit * max_fee_per_gas`.
+    pub execution_fee: U256,
+    /// The value transferred by the transaction.
+    pub value: U256,
+    /// The rollup fee of the transaction, zero for L1 messages and system transactions.
+    pub l1_fee: U256,
+}
+
+impl ScrollMaxCost {
+    /// Returns the total worst-case cost of the transaction.
+    pub fn total(&self) -> U256 {
+        self.execution_fee.saturating_add(self.value).saturating_add(self.l1_fee)
+    }
+}
+
+/// Returns the worst-case cost of the transaction in the block under the current state of the L1
+/// gas price oracle.
+///
+/// As done by the handler, the maximum fee per gas must cover the base fee of the block, and the
+/// L1 block information is loaded and the rollup fee computed through [`tx_rollup_fee`], such that
+/// the errors match the ones of the handler. The database is only read from and the caller account
+/// is left untouched.
+pub fn quote_max_cost<F: RollupFeeModel, TX: ScrollTxTr, DB: Database>(
+    fee_model: &F,
+    tx: &TX,
+    block: &impl Block,
+    db: &mut DB,
+    spec_id: ScrollSpecId,
+) -> Result<ScrollMaxCost, EVMError<DB::Error, ScrollInvalidTransaction>> {
+    let execution_fee = U256::from(tx.gas_limit()).saturating_mul(U256::from(tx.max_fee_per_gas()));
+
+    // L1 messages and system transactions neither pay the base fee nor a rollup fee.
+    if tx.is_l1_msg() || tx.is_system_tx() {
+        return Ok(ScrollMaxCost { execution_fee, value: tx.value(), l1_fee: U256::ZERO });
+    }
+
+    if tx.max_fee_per_gas() < block.basefee() as u128 {
+        return Err(EVMError::Transaction(InvalidTransaction::GasPriceLessThanBasefee.into()));
+    }
+
+    let l1_block_info = fee_model.fetch_l1_block_info(db, spec_id).map_err(EVMError::Database)?;
+    let l1_fee = tx_rollup_fee(fee_model, &l1_block_info, tx, spec_id)
+        .map_err(|err| EVMError::Transaction(ScrollInvalidTransaction::from(err)))?;
+
+    Ok(ScrollMaxCost { execution_fee, value: tx.value(), l1_fee })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::ScrollBuilder,
+        handler::ScrollHandler,
+        l1block::L1FeeError,
+        rollup_fee::ScrollRollupFeeModel,
+        test_utils::{context, ScrollContextTestUtils, CALLER, L1_DATA_COST, MIN_TRANSACTION_COST},
+        transaction::L1_MESSAGE_TYPE,
+    };
+    use revm::{
+        context::{BlockEnv, ContextTr, JournalTr},
+        handler::{EthFrame, EvmTr, Handler},
+    };
+    use std::boxed::Box;
+
+    #[test]
+    fn test_quote_max_cost() -> Result<(), Box<dyn core::error::Error>> {
+        let mut ctx = context()
+            .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+            .modify_tx_chained(|tx| tx.base.value = U256::from(1_000));
+        let (tx, block) = (ctx.tx.clone(), ctx.block.clone());
+
+        let quote =
+            quote_max_cost(&ScrollRollupFeeModel, &tx, &block, ctx.db_mut(), ScrollSpecId::CURIE)?;
+        assert_eq!(
+            quote,
+            ScrollMaxCost {
+                execution_fee: MIN_TRANSACTION_COST,
+                value: U256::from(1_000),
+                l1_fee: L1_DATA_COST
+            }
+        );
+
+        // the handler deducts the same amount, leaving the value in the account.
+        let mut evm = ctx.with_funds(quote.total()).build_scroll();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        handler.pre_execution(&mut evm)?;
+        let caller_account = evm.ctx_mut().journal_mut().load_account(CALLER)?;
+        assert_eq!(caller_account.info.balance, U256::from(1_000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_max_cost_l1_message() -> Result<(), Box<dyn core::error::Error>> {
+        let mut ctx = context().modify_tx_chained(|tx| {
+            tx.base.tx_type = L1_MESSAGE_TYPE;
+            tx.rlp_bytes = None;
+        });
+        let (tx, block) = (ctx.tx.clone(), ctx.block.clone());
+
+        let quote =
+            quote_max_cost(&ScrollRollupFeeModel, &tx, &block, ctx.db_mut(), ScrollSpecId::CURIE)?;
+        assert_eq!(quote.l1_fee, U256::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_max_cost_base_fee() -> Result<(), Box<dyn core::error::Error>> {
+        let mut ctx = context()
+            .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+            .modify_block_chained(|block| block.basefee = 1);
+        let (tx, block) = (ctx.tx.clone(), ctx.block.clone());
+
+        let quote =
+            quote_max_cost(&ScrollRollupFeeModel, &tx, &block, ctx.db_mut(), ScrollSpecId::CURIE)?;
+        assert_eq!(quote.execution_fee, MIN_TRANSACTION_COST);
+
+        // the gas price of the transaction is below the base fee of the block.
+        let block = BlockEnv { basefee: 2, ..block };
+        let err =
+            quote_max_cost(&ScrollRollupFeeModel, &tx, &block, ctx.db_mut(), ScrollSpecId::CURIE)
+                .unwrap_err();
+        assert_eq!(err, EVMError::Transaction(InvalidTransaction::GasPriceLessThanBasefee.into()));
+
+        // the handler rejects the transaction with the same error.
+        let mut evm = ctx.modify_block_chained(|block| block.basefee = 2).build_scroll();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        assert_eq!(handler.validate_env(&mut evm), Err(err));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_max_cost_missing_rlp_bytes() {
+        let mut ctx = context()
+            .with_funds(MIN_TRANSACTION_COST)
+            .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+            .modify_tx_chained(|tx| tx.rlp_bytes = None);
+        let (tx, block) = (ctx.tx.clone(), ctx.block.clone());
+
+        let err =
+            quote_max_cost(&ScrollRollupFeeModel, &tx, &block, ctx.db_mut(), ScrollSpecId::CURIE)
+                .unwrap_err();
+        assert_eq!(
+            err,
+            EVMError::Transaction(ScrollInvalidTransaction::L1Fee(L1FeeError::MissingRlpBytes))
+        );
+
+        // the handler rejects the transaction with the same error.
+        let mut evm = ctx.build_scroll();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        assert_eq!(handler.pre_execution(&mut evm), Err(err));
+    }
+}
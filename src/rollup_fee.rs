@@ -0,0 +1,78 @@
+//! Rollup fee models.
+//!
+//! The rollup fee charged to a transaction for posting its data is computed by a
+//! [`RollupFeeModel`], selected through the chain type of the context. Scroll uses the
+//! [`ScrollRollupFeeModel`], chains built on top of this crate can provide their own model.
+
+use crate::{
+    l1block::{L1BlockInfo, L1FeeError},
+    transaction::ScrollTxTr,
+    ScrollSpecId,
+};
+
+use revm::{primitives::U256, Database};
+
+/// The model used to compute the rollup fee of transactions.
+pub trait RollupFeeModel: Clone {
+    /// Fetches the L1 block information used to compute the rollup fee of the transactions in a
+    /// block.
+    ///
+    /// Defaults to reading the L1 gas price oracle storage.
+    fn fetch_l1_block_info<DB: Database>(
+        &self,
+        db: &mut DB,
+        spec_id: ScrollSpecId,
+    ) -> Result<L1BlockInfo, DB::Error> {
+        L1BlockInfo::try_fetch(db, spec_id)
+    }
+
+    /// Returns the rollup fee of the transaction with the provided RLP encoded bytes.
+    fn rollup_fee(
+        &self,
+        l1_block_info: &L1BlockInfo,
+        input: &[u8],
+        spec_id: ScrollSpecId,
+        compression_ratio: Option<U256>,
+        compressed_size: Option<usize>,
+    ) -> Result<U256, L1FeeError>;
+}
+
+/// The Scroll rollup fee model, using the formula of the spec to price the transaction data
+/// posted on L1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScrollRollupFeeModel;
+
+impl RollupFeeModel for ScrollRollupFeeModel {
+    fn rollup_fee(
+        &self,
+        l1_block_info: &L1BlockInfo,
+        input: &[u8],
+        spec_id: ScrollSpecId,
+        compression_ratio: Option<U256>,
+        compressed_size: Option<usize>,
+    ) -> Result<U256, L1FeeError> {
+        l1_block_info.try_calculate_tx_l1_cost(input, spec_id, compression_ratio, compressed_size)
+    }
+}
+
+/// Returns the rollup fee of the transaction computed by the fee model.
+///
+/// L1 messages and system transactions do not pay a rollup fee.
+pub fn tx_rollup_fee<F: RollupFeeModel, TX: ScrollTxTr>(
+    fee_model: &F,
+    l1_block_info: &L1BlockInfo,
+    tx: &TX,
+    spec_id: ScrollSpecId,
+) -> Result<U256, L1FeeError> {
+    if tx.is_l1_msg() || tx.is_system_tx() {
+        return Ok(U256::ZERO);
+    }
+    let rlp_bytes = tx.rlp_bytes().ok_or(L1FeeError::MissingRlpBytes)?;
+    fee_model.rollup_fee(
+        l1_block_info,
+        rlp_bytes,
+        spec_id,
+        tx.compression_ratio(),
+        tx.compressed_size(),
+    )
+}
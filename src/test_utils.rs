@@ -1,6 +1,7 @@
 use crate::{
     builder::{DefaultScrollContext, ScrollContext},
-    l1block::L1_GAS_PRICE_ORACLE_ADDRESS,
+    l1block::{L1BlockInfo, L1_GAS_PRICE_ORACLE_ADDRESS},
+    ScrollSpecId,
 };
 use revm::{
     database::{DbAccount, InMemoryDB},
@@ -32,16 +33,27 @@ pub fn context() -> ScrollContext<InMemoryDB> {
         .modify_block_chained(|block| block.beneficiary = BENEFICIARY)
         .with_db(InMemoryDB::default())
         .modify_db_chained(|db| {
-            let _ = db.replace_account_storage(
-                L1_GAS_PRICE_ORACLE_ADDRESS,
-                (0..7)
-                    .map(|n| (U256::from(n), U256::from(10_000)))
-                    .chain(core::iter::once((U256::from(7), TX_L1_FEE_PRECISION)))
-                    .collect(),
-            );
+            l1_block_info()
+                .write_to(db, ScrollSpecId::GALILEO)
+                .expect("the test L1 block info should be complete")
         })
 }
 
+/// Returns the [`L1BlockInfo`] stored in the L1 gas oracle of the test [`context`].
+pub fn l1_block_info() -> L1BlockInfo {
+    L1BlockInfo {
+        l1_base_fee: U256::from(10_000),
+        l1_fee_overhead: U256::from(10_000),
+        l1_base_fee_scalar: U256::from(10_000),
+        l1_blob_base_fee: Some(U256::from(10_000)),
+        l1_commit_scalar: Some(U256::from(10_000)),
+        l1_blob_scalar: Some(TX_L1_FEE_PRECISION),
+        calldata_gas: Some(U256::from(100_000_000)),
+        penalty_threshold: Some(U256::ZERO),
+        penalty_factor: Some(U256::ZERO),
+    }
+}
+
 pub trait ScrollContextTestUtils {
     fn with_funds(self, funds: U256) -> Self;
     fn with_gas_oracle_config(self, entries: Vec<(U256, U256)>) -> Self;
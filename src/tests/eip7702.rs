@@ -1,4 +1,7 @@
-use crate::{builder::ScrollBuilder, handler::ScrollHandler, test_utils::context};
+use crate::{
+    builder::ScrollBuilder, error::ScrollInvalidTransaction, handler::ScrollHandler,
+    test_utils::context,
+};
 use std::{boxed::Box, vec};
 
 use revm::{
@@ -16,7 +19,7 @@ use revm_primitives::{eip7702, U256};
 fn test_validate_initial_gas_eip7702() -> Result<(), Box<dyn core::error::Error>> {
     let ctx = context();
     let evm = ctx.clone().build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     let gas_empty_authorization_list = handler.validate_initial_tx_gas(&evm)?;
 
     let evm = ctx
@@ -34,7 +37,7 @@ fn test_validate_initial_gas_eip7702() -> Result<(), Box<dyn core::error::Error>
             ))]
         })
         .build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     let gas_with_authorization_list = handler.validate_initial_tx_gas(&evm)?;
 
     // initial gas should include eip7702 cost of authorized accounts.
@@ -58,7 +61,7 @@ fn test_validate_env_eip7702() -> Result<(), Box<dyn core::error::Error>> {
         ))]
     });
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     // eip 7702 env checks should pass.
     handler.validate_env(&mut evm)?;
@@ -0,0 +1,158 @@
+use crate::{
+    builder::{FeynmanEipActivations, ScrollBuilder},
+    estimate::{EstimateGasError, ScrollGasEstimate},
+    test_utils::{
+        context, l1_block_info, ScrollContextTestUtils, L1_DATA_COST, MIN_TRANSACTION_COST, TO,
+        TX_L1_FEE_PRECISION,
+    },
+    ScrollSpecId,
+};
+use std::boxed::Box;
+
+use revm::{
+    context::result::{EVMError, InvalidTransaction},
+    state::{AccountInfo, Bytecode},
+};
+use revm_primitives::{bytes, U256};
+
+#[test]
+fn test_estimate_gas() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| tx.base.gas_limit = 1_000_000);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let estimate = evm.estimate_gas(tx)?;
+
+    assert_eq!(
+        estimate,
+        ScrollGasEstimate { gas_limit: MIN_TRANSACTION_COST.to(), l1_fee: L1_DATA_COST }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_gas_lowers_gas_limit_to_affordable() -> Result<(), Box<dyn core::error::Error>> {
+    // the caller cannot afford the gas limit of the transaction on top of the rollup fee.
+    let ctx = context()
+        .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST + U256::from(5_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| tx.base.gas_limit = 1_000_000);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let estimate = evm.estimate_gas(tx)?;
+
+    assert_eq!(
+        estimate,
+        ScrollGasEstimate { gas_limit: MIN_TRANSACTION_COST.to(), l1_fee: L1_DATA_COST }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_gas_insufficient_funds_for_l1_fee() -> Result<(), Box<dyn core::error::Error>> {
+    // the caller can afford the execution but not the rollup fee.
+    let balance = MIN_TRANSACTION_COST + U256::from(10_000);
+    let ctx = context()
+        .with_funds(balance)
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| tx.base.gas_limit = 1_000_000);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let err = evm.estimate_gas(tx).unwrap_err();
+
+    assert_eq!(err, EstimateGasError::InsufficientFundsForL1Fee { l1_fee: L1_DATA_COST, balance });
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_gas_insufficient_funds_for_value() -> Result<(), Box<dyn core::error::Error>> {
+    // the caller cannot afford the value, the rollup fee is irrelevant.
+    let balance = U256::from(10_000);
+    let ctx = context()
+        .with_funds(balance)
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| {
+            tx.base.gas_limit = 1_000_000;
+            tx.base.value = U256::from(20_000);
+        });
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let err = evm.estimate_gas(tx).unwrap_err();
+
+    assert_eq!(
+        err,
+        EstimateGasError::Evm(EVMError::Transaction(
+            InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(U256::from(1_020_000)),
+                balance: Box::new(balance),
+            }
+            .into()
+        ))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_gas_applies_eip7623_floor_for_feynman() -> Result<(), Box<dyn core::error::Error>>
+{
+    const GAS_FLOOR: u64 = 21_080;
+    let compression_ratio = TX_L1_FEE_PRECISION;
+
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::FEYNMAN)
+        .modify_tx_chained(|tx| {
+            tx.base.gas_limit = 1_000_000;
+            tx.base.data = bytes!("0xdead");
+            tx.compression_ratio = Some(compression_ratio);
+        })
+        .maybe_with_eip_7623();
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let estimate = evm.estimate_gas(tx.clone())?;
+
+    // the execution only uses 21_032 gas, below the floor gas.
+    let l1_fee = l1_block_info().try_calculate_tx_l1_cost(
+        tx.rlp_bytes.as_deref().unwrap_or_default(),
+        ScrollSpecId::FEYNMAN,
+        Some(compression_ratio),
+        None,
+    )?;
+    assert_eq!(estimate, ScrollGasEstimate { gas_limit: GAS_FLOOR, l1_fee });
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_gas_reverted() -> Result<(), Box<dyn core::error::Error>> {
+    // REVERT(0, 0)
+    let code = Bytecode::new_raw(bytes!("0x60006000fd"));
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| tx.base.gas_limit = 1_000_000)
+        .modify_db_chained(|db| {
+            db.insert_account_info(
+                TO,
+                AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+            )
+        });
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let err = evm.estimate_gas(tx).unwrap_err();
+    assert!(matches!(err, EstimateGasError::Reverted { .. }));
+
+    Ok(())
+}
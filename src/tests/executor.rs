@@ -0,0 +1,224 @@
+use crate::{
+    block::L1MessageOrderError,
+    builder::ScrollBuilder,
+    executor::{
+        ScrollBlockExecutionError, ScrollBlockExecutionOutput, ScrollBlockExecutor,
+        ScrollBlockHooks,
+    },
+    instructions::HISTORY_STORAGE_ADDRESS,
+    test_utils::{
+        context, ScrollContextTestUtils, BENEFICIARY, CALLER, L1_DATA_COST, MIN_TRANSACTION_COST,
+        TO,
+    },
+    ScrollSpecId, ScrollTransaction, TxL1Message,
+};
+use std::{boxed::Box, vec, vec::Vec};
+
+use revm::{
+    context::{BlockEnv, ContextTr, TxEnv},
+    handler::EvmTr,
+    state::{AccountInfo, Bytecode},
+    ExecuteEvm,
+};
+use revm_primitives::{address, b256, bytes, Address, B256, U256};
+
+const L1_SENDER: Address = address!("0x000000000000000000000000000000000000beef");
+
+fn block(number: u64, gas_limit: u64) -> BlockEnv {
+    BlockEnv {
+        number: U256::from(number),
+        beneficiary: BENEFICIARY,
+        gas_limit,
+        ..Default::default()
+    }
+}
+
+fn l1_message(queue_index: u64) -> ScrollTransaction<TxEnv> {
+    TxL1Message { queue_index, gas_limit: 21_000, to: TO, sender: L1_SENDER, ..Default::default() }
+        .into()
+}
+
+fn l2_txs(count: u64) -> Vec<ScrollTransaction<TxEnv>> {
+    (0..count)
+        .map(|nonce| {
+            let mut tx = context().tx;
+            tx.base.nonce = nonce;
+            tx
+        })
+        .collect()
+}
+
+#[test]
+fn test_execute_block() -> Result<(), Box<dyn core::error::Error>> {
+    let tx_cost = MIN_TRANSACTION_COST + L1_DATA_COST;
+    let ctx = context()
+        .with_funds(tx_cost * U256::from(2))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let mut executor = ScrollBlockExecutor::new(ctx.build_scroll());
+
+    let mut txs = vec![l1_message(5)];
+    txs.extend(l2_txs(2));
+    let output = executor.execute_block(block(10, 30_000_000), B256::ZERO, 5, txs)?;
+
+    let cumulative_gas_used: Vec<_> = output.txs.iter().map(|tx| tx.cumulative_gas_used).collect();
+    assert_eq!(cumulative_gas_used, vec![21_000, 42_000, 63_000]);
+    assert_eq!(output.gas_used, 63_000);
+    assert!(output.txs.iter().all(|tx| tx.result.is_success()));
+    assert_eq!(output.next_l1_message_queue_index, 6);
+
+    // L1 messages are not charged any fees.
+    assert!(output.txs[0].fees.is_none());
+    let fees = output.txs[2].fees.as_ref().expect("L2 transactions are charged fees");
+    assert_eq!(fees.l1_fee, L1_DATA_COST);
+    assert_eq!(fees.execution_fee, MIN_TRANSACTION_COST);
+
+    // the state changes of the block are merged and committed.
+    assert_eq!(output.state[&CALLER].info.nonce, 2);
+    assert_eq!(output.state[&CALLER].info.balance, U256::ZERO);
+    assert_eq!(output.state[&L1_SENDER].info.nonce, 1);
+    let account = &executor.evm().ctx_ref().db_ref().cache.accounts[&CALLER];
+    assert_eq!(account.info.nonce, 2);
+
+    // the next block must continue the L1 message queue.
+    let err = executor
+        .execute_block(
+            block(11, 30_000_000),
+            B256::ZERO,
+            output.next_l1_message_queue_index,
+            vec![l1_message(5)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ScrollBlockExecutionError::L1MessageOrder(L1MessageOrderError::UnexpectedQueueIndex {
+            index: 0,
+            expected: 6,
+            got: 5
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_block_l1_message_queue_gap() {
+    let ctx = context().modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let mut executor = ScrollBlockExecutor::new(ctx.build_scroll());
+
+    // the first L1 message of the block skips the queue index following the parent block.
+    let err = executor
+        .execute_block(block(10, 30_000_000), B256::ZERO, 3, vec![l1_message(4)])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ScrollBlockExecutionError::L1MessageOrder(L1MessageOrderError::UnexpectedQueueIndex {
+            index: 0,
+            expected: 3,
+            got: 4
+        })
+    );
+}
+
+#[test]
+fn test_execute_block_l1_message_after_l2_tx() {
+    let ctx = context().modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let mut executor = ScrollBlockExecutor::new(ctx.build_scroll());
+
+    let mut txs = l2_txs(1);
+    txs.push(l1_message(0));
+    let err = executor.execute_block(block(10, 30_000_000), B256::ZERO, 0, txs).unwrap_err();
+    assert_eq!(
+        err,
+        ScrollBlockExecutionError::L1MessageOrder(
+            L1MessageOrderError::L1MessageAfterL2Transaction { index: 1 }
+        )
+    );
+}
+
+#[test]
+fn test_execute_block_gas_limit_exceeded() {
+    let tx_cost = MIN_TRANSACTION_COST + L1_DATA_COST;
+    let ctx = context()
+        .with_funds(tx_cost * U256::from(3))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let mut executor = ScrollBlockExecutor::new(ctx.build_scroll());
+
+    let err = executor.execute_block(block(10, 50_000), B256::ZERO, 0, l2_txs(3)).unwrap_err();
+    assert_eq!(
+        err,
+        ScrollBlockExecutionError::BlockGasLimitExceeded {
+            index: 2,
+            gas_limit: 21_000,
+            available: 8_000
+        }
+    );
+}
+
+#[test]
+fn test_execute_block_history_storage() -> Result<(), Box<dyn core::error::Error>> {
+    // stand-in for the history storage contract, storing the calldata at slot `number - 1`.
+    let bytecode = Bytecode::new_raw(bytes!("5f35600143035500"));
+    let ctx = context()
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::FEYNMAN)
+        .modify_db_chained(|db| {
+            db.insert_account_info(
+                HISTORY_STORAGE_ADDRESS,
+                AccountInfo {
+                    code_hash: bytecode.hash_slow(),
+                    code: Some(bytecode),
+                    ..Default::default()
+                },
+            )
+        });
+    let mut executor = ScrollBlockExecutor::new(ctx.build_scroll());
+
+    let parent_hash = b256!("0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6");
+    let output = executor.execute_block(block(10, 30_000_000), parent_hash, 0, Vec::new())?;
+
+    let slot = &output.state[&HISTORY_STORAGE_ADDRESS].storage[&U256::from(9)];
+    assert_eq!(slot.present_value, U256::from_be_bytes(parent_hash.0));
+    assert_eq!(output.gas_used, 0);
+    assert!(output.txs.is_empty());
+
+    // the system call is not executed for the genesis block.
+    let output = executor.execute_block(block(0, 30_000_000), B256::ZERO, 0, Vec::new())?;
+    assert!(output.state.is_empty());
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct CountingHooks {
+    pre_block: usize,
+    post_block: usize,
+}
+
+impl<EVM: ExecuteEvm> ScrollBlockHooks<EVM> for CountingHooks {
+    fn pre_block(&mut self, _evm: &mut EVM) -> Result<(), EVM::Error> {
+        self.pre_block += 1;
+        Ok(())
+    }
+
+    fn post_block(
+        &mut self,
+        _evm: &mut EVM,
+        output: &mut ScrollBlockExecutionOutput,
+    ) -> Result<(), EVM::Error> {
+        assert_eq!(output.txs.len(), 1);
+        self.post_block += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_execute_block_hooks() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context().modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let mut executor =
+        ScrollBlockExecutor::new(ctx.build_scroll()).with_hooks(CountingHooks::default());
+
+    executor.execute_block(block(10, 30_000_000), B256::ZERO, 0, vec![l1_message(0)])?;
+    assert_eq!(executor.hooks().pre_block, 1);
+    assert_eq!(executor.hooks().post_block, 1);
+
+    Ok(())
+}
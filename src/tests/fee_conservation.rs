@@ -0,0 +1,258 @@
+use crate::{
+    builder::{FeynmanEipActivations, ScrollBuilder},
+    chain::{ScrollFeeRouting, L2_TX_FEE_VAULT_ADDRESS},
+    fee_conservation::{
+        check_fee_conservation, FeeMismatch, ScrollFeeFlow, ValueTransfer, ValueTransferInspector,
+    },
+    test_utils::{
+        context, ScrollContextTestUtils, BENEFICIARY, CALLER, L1_DATA_COST, MIN_TRANSACTION_COST,
+        TO,
+    },
+    transaction::{L1_MESSAGE_TYPE, SYSTEM_ADDRESS},
+    ScrollSpecId, ScrollTransaction,
+};
+use std::{boxed::Box, vec};
+
+use revm::{
+    context::ContextTr,
+    handler::{EvmTr, SystemCallTx},
+    primitives::{address, bytes, Address, Bytes},
+    state::{AccountInfo, Bytecode},
+};
+use revm_primitives::U256;
+
+#[test]
+fn test_fee_conservation() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(U256::from(100_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| tx.base.gas_limit = 50_000);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (result, report) = evm.transact_with_fee_check(tx)?;
+
+    assert!(result.result.is_success());
+    assert!(report.is_conserved());
+    assert_eq!(
+        report.flow,
+        ScrollFeeFlow {
+            caller: CALLER,
+            debit: U256::from(50_000) + L1_DATA_COST,
+            refund: U256::from(29_000),
+            credits: vec![(BENEFICIARY, MIN_TRANSACTION_COST + L1_DATA_COST)],
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_callee_transfer_to_beneficiary() -> Result<(), Box<dyn core::error::Error>>
+{
+    // calls the beneficiary with a value of 1 wei.
+    let code = Bytecode::new_raw(bytes!("0x6000600060006000600160025af100"));
+    let ctx = context()
+        .with_funds(U256::from(1_000_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| {
+            tx.base.gas_limit = 100_000;
+            tx.base.value = U256::from(100);
+        })
+        .modify_db_chained(|db| {
+            let info =
+                AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() };
+            db.insert_account_info(TO, info);
+        });
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (result, report) = evm.transact_with_fee_check(tx)?;
+
+    // the transfer to the beneficiary is not mistaken for a fee credit.
+    assert!(result.result.is_success());
+    assert!(report.is_conserved());
+    assert_eq!(
+        report.transfers,
+        vec![
+            ValueTransfer { from: CALLER, to: TO, value: U256::from(100) },
+            ValueTransfer { from: TO, to: BENEFICIARY, value: U256::from(1) }
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_eip7623_floor() -> Result<(), Box<dyn core::error::Error>> {
+    const GAS_LIMIT: u64 = 50_000;
+    const GAS_FLOOR: u64 = 21_080;
+
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::FEYNMAN)
+        .modify_tx_chained(|tx| {
+            tx.base.gas_limit = GAS_LIMIT;
+            tx.base.data = bytes!("0xdead");
+        })
+        .maybe_with_eip_7623();
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (result, report) = evm.transact_with_fee_check(tx)?;
+    let l1_fee = evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    // the caller is charged for the floor rather than the gas spent.
+    assert_eq!(result.result.gas_used(), GAS_FLOOR);
+    assert!(report.is_conserved());
+    assert_eq!(report.flow.refund, U256::from(GAS_LIMIT - GAS_FLOOR));
+    assert_eq!(report.flow.credit(), U256::from(GAS_FLOOR) + l1_fee);
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_fee_routing() -> Result<(), Box<dyn core::error::Error>> {
+    const EXECUTION_FEE_RECIPIENT: Address = address!("0x0000000000000000000000000000000000000003");
+    let ctx = context()
+        .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST)
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_chain_chained(|chain| {
+            chain.fee_routing = Some(ScrollFeeRouting {
+                l1_fee_recipient: L2_TX_FEE_VAULT_ADDRESS,
+                execution_fee_recipient: EXECUTION_FEE_RECIPIENT,
+            })
+        });
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (_, report) = evm.transact_with_fee_check(tx)?;
+
+    assert!(report.is_conserved());
+    assert_eq!(
+        report.flow.credits,
+        vec![
+            (EXECUTION_FEE_RECIPIENT, MIN_TRANSACTION_COST),
+            (L2_TX_FEE_VAULT_ADDRESS, L1_DATA_COST)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_l1_message() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (_, report) = evm.transact_with_fee_check(tx)?;
+
+    // l1 messages are not charged and the beneficiary is not credited.
+    assert!(report.is_conserved());
+    assert_eq!(report.flow.debit, U256::ZERO);
+    assert_eq!(report.flow.credits, vec![(BENEFICIARY, U256::ZERO)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_system_tx() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context().modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE).modify_tx_chained(
+        |tx| *tx = ScrollTransaction::new_system_tx_with_caller(SYSTEM_ADDRESS, TO, Bytes::new()),
+    );
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (_, report) = evm.transact_with_fee_check(tx)?;
+
+    // system transactions do not pay a rollup fee.
+    assert!(report.is_conserved());
+    assert_eq!(report.flow.debit, U256::ZERO);
+    assert_eq!(report.flow.credit(), U256::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_mismatch() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST)
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (mut result, report) = evm.transact_with_fee_check(tx)?;
+    assert!(report.is_conserved());
+
+    // over credit the beneficiary.
+    let expected = MIN_TRANSACTION_COST + L1_DATA_COST;
+    let beneficiary = result.state.get_mut(&BENEFICIARY).expect("beneficiary should be touched");
+    beneficiary.info.balance += U256::from(1);
+
+    let report = check_fee_conservation(
+        evm.ctx_mut().db_mut(),
+        report.flow,
+        report.transfers,
+        &result.state,
+    )?;
+    assert_eq!(
+        report.mismatches,
+        vec![
+            FeeMismatch::Supply { increase: expected + U256::from(1), decrease: expected },
+            FeeMismatch::Credit {
+                recipient: BENEFICIARY,
+                expected,
+                received: U256::ZERO,
+                sent: U256::ZERO,
+                pre_balance: U256::ZERO,
+                post_balance: expected + U256::from(1),
+            }
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_conservation_debit_mismatch() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST + U256::from(1_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| tx.base.value = U256::from(100));
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll_with_inspector(ValueTransferInspector::default());
+    let (mut result, report) = evm.transact_with_fee_check(tx)?;
+    assert!(report.is_conserved());
+    assert_eq!(
+        report.transfers,
+        vec![ValueTransfer { from: CALLER, to: TO, value: U256::from(100) }]
+    );
+
+    // over debit the caller and move the excess to the target, keeping the supply unchanged.
+    let caller = result.state.get_mut(&CALLER).expect("caller should be touched");
+    caller.info.balance -= U256::from(1);
+    let target = result.state.get_mut(&TO).expect("target should be touched");
+    target.info.balance += U256::from(1);
+
+    let report = check_fee_conservation(
+        evm.ctx_mut().db_mut(),
+        report.flow,
+        report.transfers,
+        &result.state,
+    )?;
+    assert_eq!(
+        report.mismatches,
+        vec![FeeMismatch::Debit {
+            caller: CALLER,
+            expected: MIN_TRANSACTION_COST + L1_DATA_COST,
+            received: U256::ZERO,
+            sent: U256::from(100),
+            pre_balance: MIN_TRANSACTION_COST + L1_DATA_COST + U256::from(1_000),
+            post_balance: U256::from(899),
+        }]
+    );
+
+    Ok(())
+}
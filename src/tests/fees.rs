@@ -1,17 +1,28 @@
 use crate::{
     builder::ScrollBuilder,
+    chain::ScrollChainContext,
+    error::ScrollInvalidTransaction,
     handler::ScrollHandler,
     l1block::*,
-    test_utils::{context, ScrollContextTestUtils, BENEFICIARY, CALLER},
-    transaction::SYSTEM_ADDRESS,
-    ScrollSpecId,
+    rollup_fee::RollupFeeModel,
+    test_utils::{
+        context, l1_block_info, ScrollContextTestUtils, BENEFICIARY, CALLER, L1_DATA_COST,
+        MIN_TRANSACTION_COST, TO,
+    },
+    transaction::{ScrollTxTr, SYSTEM_ADDRESS},
+    ScrollSpecId, ScrollTransaction,
 };
 use revm::{
-    context::{result::EVMError, ContextTr, JournalTr},
-    handler::{EthFrame, EvmTr, FrameResult, Handler},
+    context::{
+        result::{EVMError, ResultAndState},
+        BlockEnv, ContextTr, JournalTr,
+    },
+    handler::{EthFrame, EvmTr, FrameResult, Handler, SystemCallTx},
     interpreter::{CallOutcome, Gas, InstructionResult, InterpreterResult},
+    state::Bytecode,
+    Database, ExecuteCommitEvm, ExecuteEvm,
 };
-use revm_primitives::U256;
+use revm_primitives::{bytes, Bytes, TxKind, U256};
 use std::{boxed::Box, vec};
 
 #[test]
@@ -20,7 +31,7 @@ fn test_should_deduct_correct_fees_bernoulli() -> Result<(), Box<dyn core::error
         .with_funds(U256::from(30_000))
         .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::BERNOULLI);
     let mut evm = ctx.clone().build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     handler.pre_execution(&mut evm).unwrap();
 
@@ -39,7 +50,7 @@ fn test_should_deduct_correct_fees_curie() -> Result<(), Box<dyn core::error::Er
         .with_funds(U256::from(70_000))
         .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
     let mut evm = ctx.clone().build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     handler.pre_execution(&mut evm).unwrap();
 
@@ -58,12 +69,11 @@ fn test_no_rollup_fee_for_system_tx() -> Result<(), Box<dyn core::error::Error>>
         .with_funds(U256::from(70_000))
         .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
         .modify_tx_chained(|tx| {
-            tx.base.caller = SYSTEM_ADDRESS;
-            tx.base.gas_price = 0
+            *tx = ScrollTransaction::new_system_tx_with_caller(SYSTEM_ADDRESS, TO, Bytes::new())
         });
 
     let mut evm = ctx.clone().build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     handler.pre_execution(&mut evm).unwrap();
 
@@ -78,12 +88,15 @@ fn test_no_rollup_fee_for_system_tx() -> Result<(), Box<dyn core::error::Error>>
 
 #[test]
 fn test_reward_beneficiary_system_tx() -> Result<(), Box<dyn core::error::Error>> {
-    let ctx = context()
-        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
-        .modify_tx_chained(|tx| tx.base.caller = SYSTEM_ADDRESS);
+    let ctx = context().modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE).modify_tx_chained(
+        |tx| {
+            *tx = ScrollTransaction::new_system_tx_with_caller(SYSTEM_ADDRESS, TO, Bytes::new());
+            tx.base.gas_price = 1;
+        },
+    );
 
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     let gas = Gas::new_spent(21000);
     let mut result = FrameResult::Call(CallOutcome::new(
         InterpreterResult { result: InstructionResult::Return, output: Default::default(), gas },
@@ -100,6 +113,55 @@ fn test_reward_beneficiary_system_tx() -> Result<(), Box<dyn core::error::Error>
     Ok(())
 }
 
+#[test]
+fn test_reject_system_caller() -> Result<(), Box<dyn core::error::Error>> {
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+
+    // ordinary transactions cannot use the system address as caller.
+    let mut evm = context().modify_tx_chained(|tx| tx.base.caller = SYSTEM_ADDRESS).build_scroll();
+    assert_eq!(
+        handler.validate_env(&mut evm),
+        Err(EVMError::Transaction(ScrollInvalidTransaction::SystemCallerNotAllowed))
+    );
+
+    let mut evm = context()
+        .modify_tx_chained(|tx| {
+            *tx = ScrollTransaction::new_system_tx_with_caller(SYSTEM_ADDRESS, TO, Bytes::new())
+        })
+        .build_scroll();
+    handler.validate_env(&mut evm)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_forced_system_tx_flag_pays_rollup_fee() -> Result<(), Box<dyn core::error::Error>> {
+    // an ordinary transaction built as a system transaction for another caller.
+    let ctx = context()
+        .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST)
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_tx_chained(|tx| {
+            let ordinary = tx.clone();
+            *tx = ScrollTransaction::new_system_tx_with_caller(CALLER, TO, Bytes::new());
+            tx.base.gas_price = ordinary.base.gas_price;
+            tx.base.gas_limit = ordinary.base.gas_limit;
+            tx.rlp_bytes = ordinary.rlp_bytes;
+        });
+
+    let mut evm = ctx.build_scroll();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+    assert!(!evm.ctx().tx().is_system_tx());
+    handler.validate_env(&mut evm)?;
+    handler.pre_execution(&mut evm)?;
+
+    // the gas fee and the rollup fee are deducted.
+    let caller_account = evm.ctx_mut().journal_mut().load_account(CALLER)?;
+    assert_eq!(caller_account.data.info.balance, U256::ZERO);
+    assert_eq!(evm.tx_fees().map(|fees| fees.l1_fee), Some(L1_DATA_COST));
+
+    Ok(())
+}
+
 #[test]
 fn test_should_deduct_correct_fees_feynman() -> Result<(), Box<dyn core::error::Error>> {
     let initial_funds = U256::from(70_000);
@@ -123,7 +185,7 @@ fn test_should_deduct_correct_fees_feynman() -> Result<(), Box<dyn core::error::
         .with_tx_payload(tx_payload.into());
 
     let mut evm = ctx.clone().build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     handler.pre_execution(&mut evm).unwrap();
 
@@ -136,3 +198,260 @@ fn test_should_deduct_correct_fees_feynman() -> Result<(), Box<dyn core::error::
 
     Ok(())
 }
+
+#[test]
+fn test_should_reject_tx_on_rollup_fee_error() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(U256::from(70_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::FEYNMAN);
+
+    let mut evm = ctx.build_scroll();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+
+    // the transaction is missing its compression ratio.
+    let err = handler.pre_execution(&mut evm).unwrap_err();
+    assert_eq!(
+        err,
+        EVMError::Transaction(ScrollInvalidTransaction::L1Fee(
+            L1FeeError::MissingCompressionRatio { spec_id: ScrollSpecId::FEYNMAN }
+        ))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_should_expose_charged_fees() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(U256::from(70_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let tx = ctx.tx.clone();
+
+    let mut evm = ctx.build_scroll();
+    let ResultAndState { state, .. } = evm.transact(tx)?;
+
+    // the exposed fees should match the rollup fee and the gas fee charged.
+    let tx_fees = evm.tx_fees().cloned().expect("fees should be recorded");
+    assert_eq!(tx_fees.l1_fee, U256::from(40_000));
+    assert_eq!(tx_fees.execution_fee, U256::from(21_000));
+    assert_eq!(tx_fees.l1_block_info, evm.ctx().chain.l1_block_info);
+
+    // the beneficiary should be credited with both.
+    let beneficiary = state.get(&BENEFICIARY).expect("beneficiary should be touched");
+    assert_eq!(beneficiary.info.balance, tx_fees.l1_fee + tx_fees.execution_fee);
+
+    Ok(())
+}
+
+/// A rollup fee model charging a flat fee, independent of the L1 gas price oracle.
+#[derive(Clone)]
+struct FlatRollupFeeModel(U256);
+
+impl RollupFeeModel for FlatRollupFeeModel {
+    fn fetch_l1_block_info<DB: Database>(
+        &self,
+        _db: &mut DB,
+        _spec_id: ScrollSpecId,
+    ) -> Result<L1BlockInfo, DB::Error> {
+        Ok(L1BlockInfo::default())
+    }
+
+    fn rollup_fee(
+        &self,
+        _l1_block_info: &L1BlockInfo,
+        _input: &[u8],
+        _spec_id: ScrollSpecId,
+        _compression_ratio: Option<U256>,
+        _compressed_size: Option<usize>,
+    ) -> Result<U256, L1FeeError> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn test_should_charge_custom_rollup_fee_model() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(U256::from(26_000))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::FEYNMAN)
+        .with_chain(ScrollChainContext::with_fee_model(FlatRollupFeeModel(U256::from(5_000))));
+    let tx = ctx.tx.clone();
+
+    // the flat model does not require the compression ratio of the transaction.
+    let mut evm = ctx.build_scroll();
+    let ResultAndState { state, .. } = evm.transact(tx)?;
+
+    let tx_fees = evm.tx_fees().cloned().expect("fees should be recorded");
+    assert_eq!(tx_fees.l1_fee, U256::from(5_000));
+    assert_eq!(tx_fees.l1_block_info, L1BlockInfo::default());
+
+    let caller = state.get(&CALLER).expect("caller should be touched");
+    assert_eq!(caller.info.balance, U256::ZERO);
+    let beneficiary = state.get(&BENEFICIARY).expect("beneficiary should be touched");
+    assert_eq!(beneficiary.info.balance, U256::from(26_000));
+
+    Ok(())
+}
+
+#[test]
+fn test_cached_l1_block_info_matches_uncached_after_oracle_update(
+) -> Result<(), Box<dyn core::error::Error>> {
+    // stores the first word of the calldata in the L1 base fee slot of the oracle.
+    let oracle_code = Bytecode::new_raw(bytes!("0x60003560015500"));
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_db_chained(|db| {
+            let mut info = db.load_account(L1_GAS_PRICE_ORACLE_ADDRESS).unwrap().info.clone();
+            info.code_hash = oracle_code.hash_slow();
+            info.code = Some(oracle_code);
+            db.insert_account_info(L1_GAS_PRICE_ORACLE_ADDRESS, info);
+        });
+    let tx = ctx.tx.clone();
+    let mut evm = ctx.clone().build_scroll();
+
+    // first transaction of the block fetches the L1 block info.
+    evm.transact_commit(tx.clone())?;
+    let initial_l1_fee = evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    // second transaction updates the L1 base fee in the oracle.
+    let mut update_tx = tx.clone();
+    update_tx.base.nonce = 1;
+    update_tx.base.kind = TxKind::Call(L1_GAS_PRICE_ORACLE_ADDRESS);
+    update_tx.base.data = U256::from(1_000_000_000u64).to_be_bytes_vec().into();
+    update_tx.base.gas_limit = 100_000;
+    evm.transact_commit(update_tx)?;
+
+    // third transaction should use the updated L1 block info.
+    let mut last_tx = tx;
+    last_tx.base.nonce = 2;
+    evm.transact_commit(last_tx.clone())?;
+    let cached_l1_fee = evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    // compare against a fresh evm on top of the same state.
+    let db = evm.ctx().db_mut().clone();
+    let mut uncached_evm = ctx.with_db(db).build_scroll();
+    uncached_evm.transact(last_tx)?;
+    let uncached_l1_fee = uncached_evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    assert_eq!(cached_l1_fee, uncached_l1_fee);
+    assert_ne!(cached_l1_fee, initial_l1_fee);
+
+    Ok(())
+}
+
+#[test]
+fn test_cached_l1_block_info_matches_uncached_after_unfinalized_oracle_update(
+) -> Result<(), Box<dyn core::error::Error>> {
+    // stores the first word of the calldata in the L1 base fee slot of the oracle.
+    let oracle_code = Bytecode::new_raw(bytes!("0x60003560015500"));
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE)
+        .modify_db_chained(|db| {
+            let mut info = db.load_account(L1_GAS_PRICE_ORACLE_ADDRESS).unwrap().info.clone();
+            info.code_hash = oracle_code.hash_slow();
+            info.code = Some(oracle_code);
+            db.insert_account_info(L1_GAS_PRICE_ORACLE_ADDRESS, info);
+        });
+    let tx = ctx.tx.clone();
+    let mut evm = ctx.clone().build_scroll();
+
+    // the transactions of the block are executed without finalizing the state in between.
+    evm.transact_one(tx.clone())?;
+    let initial_l1_fee = evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    let mut update_tx = tx.clone();
+    update_tx.base.nonce = 1;
+    update_tx.base.kind = TxKind::Call(L1_GAS_PRICE_ORACLE_ADDRESS);
+    update_tx.base.data = U256::from(1_000_000_000u64).to_be_bytes_vec().into();
+    update_tx.base.gas_limit = 100_000;
+    assert!(evm.transact_one(update_tx)?.is_success());
+
+    let mut last_tx = tx;
+    last_tx.base.nonce = 2;
+    evm.transact_one(last_tx.clone())?;
+    let cached_l1_fee = evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    // compare against a fresh evm on top of the finalized state.
+    let state = evm.finalize();
+    evm.commit(state);
+    let db = evm.ctx().db_mut().clone();
+    let mut uncached_evm = ctx.with_db(db).build_scroll();
+    last_tx.base.nonce = 3;
+    uncached_evm.transact(last_tx)?;
+    let uncached_l1_fee = uncached_evm.tx_fees().expect("fees should be recorded").l1_fee;
+
+    assert_eq!(cached_l1_fee, uncached_l1_fee);
+    assert_ne!(cached_l1_fee, initial_l1_fee);
+
+    Ok(())
+}
+
+#[test]
+fn test_cached_l1_block_info_invalidated_on_new_block_and_evm(
+) -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(U256::from(10u64.pow(18)))
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let tx = ctx.tx.clone();
+    let mut evm = ctx.clone().build_scroll();
+    evm.transact_commit(tx.clone())?;
+    assert_eq!(evm.tx_fees().expect("fees should be recorded").l1_fee, L1_DATA_COST);
+
+    // the oracle is written outside of a transaction, e.g. by a pre-block hook.
+    let updated = L1BlockInfo { l1_base_fee: U256::from(20_000), ..l1_block_info() };
+    let expected = updated.try_calculate_tx_l1_cost(
+        tx.rlp_bytes.as_ref().expect("rlp bytes should be set"),
+        ScrollSpecId::CURIE,
+        None,
+        None,
+    )?;
+    assert_ne!(expected, L1_DATA_COST);
+    updated.write_to(evm.ctx().db_mut(), ScrollSpecId::GALILEO)?;
+
+    // the next block fetches the L1 block info again.
+    evm.set_block(BlockEnv {
+        number: U256::from(1),
+        beneficiary: BENEFICIARY,
+        ..Default::default()
+    });
+    let mut next_tx = tx.clone();
+    next_tx.base.nonce = 1;
+    evm.transact_commit(next_tx)?;
+    assert_eq!(evm.tx_fees().expect("fees should be recorded").l1_fee, expected);
+
+    // a chain context cached for another database is not reused.
+    let chain = evm.ctx().chain.clone();
+    assert!(chain.is_l1_block_info_cached(U256::from(1), ScrollSpecId::CURIE));
+    let mut other_evm = ctx
+        .with_chain(chain)
+        .modify_block_chained(|block| block.number = U256::from(1))
+        .build_scroll();
+    other_evm.transact(tx)?;
+    assert_eq!(other_evm.tx_fees().expect("fees should be recorded").l1_fee, L1_DATA_COST);
+
+    Ok(())
+}
+
+#[test]
+fn test_tx_fees_cleared_on_invalid_tx() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .with_funds(MIN_TRANSACTION_COST + L1_DATA_COST)
+        .modify_cfg_chained(|cfg| cfg.spec = ScrollSpecId::CURIE);
+    let tx = ctx.tx.clone();
+    let mut evm = ctx.build_scroll();
+
+    evm.transact_one(tx.clone())?;
+    assert_eq!(evm.tx_fees().map(|fees| fees.l1_fee), Some(L1_DATA_COST));
+
+    // the fees of the previous transaction are not reported for a transaction failing validation.
+    let mut invalid_tx = tx;
+    invalid_tx.base.caller = SYSTEM_ADDRESS;
+    assert_eq!(
+        evm.transact_one(invalid_tx),
+        Err(EVMError::Transaction(ScrollInvalidTransaction::SystemCallerNotAllowed))
+    );
+    assert_eq!(evm.tx_fees(), None);
+
+    Ok(())
+}
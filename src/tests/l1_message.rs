@@ -1,11 +1,13 @@
 use crate::{
+    alias::StrictL1Sender,
     builder::ScrollBuilder,
+    error::ScrollInvalidTransaction,
     handler::ScrollHandler,
     l1block::L1BlockInfo,
     test_utils::{context, BENEFICIARY, CALLER},
     transaction::L1_MESSAGE_TYPE,
 };
-use std::boxed::Box;
+use std::{boxed::Box, vec};
 
 use crate::test_utils::MIN_TRANSACTION_COST;
 use revm::{
@@ -29,7 +31,7 @@ use revm_primitives::{bytes, hardfork::SpecId, U256};
 fn test_l1_message_validate_lacking_funds() -> Result<(), Box<dyn core::error::Error>> {
     let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     // pre execution includes fees deduction, which should be skipped for l1 messages.
     handler.pre_execution(&mut evm)?;
@@ -41,11 +43,11 @@ fn test_l1_message_validate_lacking_funds() -> Result<(), Box<dyn core::error::E
 fn test_l1_message_load_accounts() -> Result<(), Box<dyn core::error::Error>> {
     let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     handler.load_accounts(&mut evm)?;
 
     // l1 block info should not be loaded for l1 messages.
-    let l1_block_info = evm.ctx().chain.clone();
+    let l1_block_info = evm.ctx().chain.l1_block_info.clone();
     assert_eq!(l1_block_info, L1BlockInfo::default());
 
     Ok(())
@@ -56,7 +58,7 @@ fn test_l1_message_should_not_deduct_caller() -> Result<(), Box<dyn core::error:
     let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
 
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     handler.load_accounts(&mut evm)?;
     handler.validate_against_state_and_deduct_caller(&mut evm)?;
 
@@ -74,7 +76,7 @@ fn test_l1_message_last_frame_result() -> Result<(), Box<dyn core::error::Error>
     let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
 
     let mut evm = ctx.build_scroll();
-    let mut handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let mut handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     let mut gas = Gas::new(21000);
     gas.set_refund(10);
     gas.set_spent(10);
@@ -96,7 +98,7 @@ fn test_l1_message_should_not_refund() -> Result<(), Box<dyn core::error::Error>
     let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
 
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     let mut gas = Gas::new(21000);
     gas.set_refund(10);
     gas.set_spent(10);
@@ -117,7 +119,7 @@ fn test_l1_message_should_not_reward_beneficiary() -> Result<(), Box<dyn core::e
     let ctx = context().modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE);
 
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
     let gas = Gas::new_spent(21000);
     let mut result = FrameResult::Call(CallOutcome::new(
         InterpreterResult { result: InstructionResult::Return, output: Default::default(), gas },
@@ -168,7 +170,7 @@ fn test_l1_message_should_pass_validation() -> Result<(), Box<dyn core::error::E
         // set the base fee of the block above the L1 message gas price to check it passes.
         .modify_block_chained(|block| block.basefee = 100);
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     handler.validate(&mut evm)?;
 
@@ -187,7 +189,7 @@ fn test_l1_message_should_pass_pre_execution() -> Result<(), Box<dyn core::error
             caller.data.info.nonce += 1;
         });
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     handler.pre_execution(&mut evm)?;
 
@@ -207,10 +209,52 @@ fn test_l1_message_eip_3607() -> Result<(), Box<dyn core::error::Error>> {
                 Some(Bytecode::LegacyAnalyzed(LegacyRawBytecode([1u8; 2].into()).into_analyzed()));
         });
     let mut evm = ctx.build_scroll();
-    let handler = ScrollHandler::<_, EVMError<_>, EthFrame<_>>::new();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
 
     let err = handler.pre_execution(&mut evm).unwrap_err();
-    assert_eq!(err, EVMError::Transaction(InvalidTransaction::RejectCallerWithCode));
+    assert_eq!(
+        err,
+        EVMError::Transaction(ScrollInvalidTransaction::Base(
+            InvalidTransaction::RejectCallerWithCode
+        ))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_l1_message_strict_sender() -> Result<(), Box<dyn core::error::Error>> {
+    let ctx = context()
+        .modify_tx_chained(|tx| tx.base.tx_type = L1_MESSAGE_TYPE)
+        .modify_chain_chained(|chain| chain.strict_l1_sender = Some(StrictL1Sender::default()));
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+
+    // a sender with code is rejected with a dedicated error.
+    let mut evm = ctx
+        .clone()
+        .modify_journal_chained(|journal| {
+            let caller = journal.load_account(CALLER).unwrap();
+            caller.data.info.code =
+                Some(Bytecode::LegacyAnalyzed(LegacyRawBytecode([1u8; 2].into()).into_analyzed()));
+        })
+        .build_scroll();
+    let err = handler.pre_execution(&mut evm).unwrap_err();
+    assert_eq!(
+        err,
+        EVMError::Transaction(ScrollInvalidTransaction::L1SenderWithCode { sender: CALLER })
+    );
+
+    // a known L1 contract must be aliased.
+    let mut evm = ctx
+        .modify_chain_chained(|chain| {
+            chain.strict_l1_sender = Some(StrictL1Sender::new(vec![CALLER]))
+        })
+        .build_scroll();
+    let err = handler.pre_execution(&mut evm).unwrap_err();
+    assert_eq!(
+        err,
+        EVMError::Transaction(ScrollInvalidTransaction::UnaliasedL1Sender { sender: CALLER })
+    );
 
     Ok(())
 }
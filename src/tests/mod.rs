@@ -1,4 +1,8 @@
 mod eip7623;
 mod eip7702;
+mod estimate_gas;
+mod executor;
+mod fee_conservation;
 mod fees;
 mod l1_message;
+mod tx_type;
@@ -0,0 +1,80 @@
+use crate::{
+    builder::ScrollBuilder, error::ScrollInvalidTransaction, handler::ScrollHandler,
+    test_utils::context, transaction::L1_MESSAGE_TYPE, ScrollSpecId,
+};
+use std::{boxed::Box, vec};
+
+use revm::{
+    context::{result::EVMError, TransactionType},
+    handler::{EthFrame, Handler},
+};
+use revm_primitives::B256;
+use rstest::rstest;
+
+#[rstest]
+#[case(TransactionType::Legacy as u8, ScrollSpecId::SHANGHAI, true)]
+#[case(TransactionType::Eip2930 as u8, ScrollSpecId::SHANGHAI, true)]
+#[case(TransactionType::Eip1559 as u8, ScrollSpecId::BERNOULLI, false)]
+#[case(TransactionType::Eip1559 as u8, ScrollSpecId::CURIE, true)]
+#[case(TransactionType::Eip7702 as u8, ScrollSpecId::DARWIN, false)]
+#[case(TransactionType::Eip7702 as u8, ScrollSpecId::EUCLID, true)]
+#[case(TransactionType::Eip4844 as u8, ScrollSpecId::GALILEO, false)]
+#[case(L1_MESSAGE_TYPE, ScrollSpecId::SHANGHAI, true)]
+#[case(0x05, ScrollSpecId::GALILEO, false)]
+fn test_validate_env_tx_type(
+    #[case] tx_type: u8,
+    #[case] spec_id: ScrollSpecId,
+    #[case] supported: bool,
+) -> Result<(), Box<dyn core::error::Error>> {
+    let mut evm = context()
+        .modify_cfg_chained(|cfg| cfg.spec = spec_id)
+        .modify_tx_chained(|tx| tx.base.tx_type = tx_type)
+        .build_scroll();
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+
+    let result = handler.validate_env(&mut evm);
+    if supported {
+        // the transaction may still fail the Ethereum environment checks.
+        assert!(!matches!(
+            result,
+            Err(EVMError::Transaction(ScrollInvalidTransaction::UnsupportedTxType { .. }))
+        ));
+    } else {
+        assert_eq!(
+            result,
+            Err(EVMError::Transaction(ScrollInvalidTransaction::UnsupportedTxType {
+                tx_type,
+                spec_id
+            }))
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_env_blob_fields() -> Result<(), Box<dyn core::error::Error>> {
+    let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+
+    let mut evm = context()
+        .modify_tx_chained(|tx| {
+            tx.base.tx_type = TransactionType::Eip1559 as u8;
+            tx.base.blob_hashes = vec![B256::ZERO];
+        })
+        .build_scroll();
+    assert_eq!(
+        handler.validate_env(&mut evm),
+        Err(EVMError::Transaction(ScrollInvalidTransaction::BlobFieldsNotSupported))
+    );
+
+    let mut evm = context().modify_tx_chained(|tx| tx.base.max_fee_per_blob_gas = 1).build_scroll();
+    assert_eq!(
+        handler.validate_env(&mut evm),
+        Err(EVMError::Transaction(ScrollInvalidTransaction::BlobFieldsNotSupported))
+    );
+
+    let mut evm = context().build_scroll();
+    handler.validate_env(&mut evm)?;
+
+    Ok(())
+}
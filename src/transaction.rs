@@ -1,8 +1,11 @@
+use std::vec::Vec;
+
+use alloy_rlp::{BufMut, Decodable, Encodable, Header};
 use revm::{
     context::{Transaction, TxEnv},
     handler::SystemCallTx,
 };
-use revm_primitives::{address, Address, Bytes, TxKind, B256, U256};
+use revm_primitives::{address, keccak256, Address, Bytes, TxKind, B256, U256};
 
 /// The type for a l1 message transaction.
 pub const L1_MESSAGE_TYPE: u8 = 0x7E;
@@ -15,7 +18,8 @@ pub trait ScrollTxTr: Transaction {
     /// Whether the transaction is an L1 message.
     fn is_l1_msg(&self) -> bool;
 
-    /// Whether the transaction is a system transaction (e.g. EIP-2935).
+    /// Whether the transaction is a system transaction (e.g. EIP-2935), created through
+    /// [`SystemCallTx::new_system_tx_with_caller`] with the [`SYSTEM_ADDRESS`] as caller.
     fn is_system_tx(&self) -> bool;
 
     /// The RLP encoded transaction bytes which are used to calculate the cost associated with
@@ -31,6 +35,10 @@ pub trait ScrollTxTr: Transaction {
     /// This is used for calculating the cost associated with posting the transaction on L1.
     /// Note: compressed_size(tx) = min(size(zstd(rlp(tx))), size(rlp(tx)))
     fn compressed_size(&self) -> Option<usize>;
+
+    /// The index of the L1 message in the L1 message queue, None if the transaction is not an L1
+    /// message.
+    fn queue_index(&self) -> Option<u64>;
 }
 
 /// A Scroll transaction. Wraps around a base transaction and provides the optional RLPed bytes for
@@ -42,6 +50,11 @@ pub struct ScrollTransaction<T: Transaction> {
     pub rlp_bytes: Option<Bytes>,
     pub compression_ratio: Option<U256>,
     pub compressed_size: Option<usize>,
+    pub queue_index: Option<u64>,
+    /// Whether the transaction is a system transaction, only set through
+    /// [`SystemCallTx::new_system_tx_with_caller`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    system_tx: bool,
 }
 
 impl<T: Transaction> ScrollTransaction<T> {
@@ -51,7 +64,30 @@ impl<T: Transaction> ScrollTransaction<T> {
         compression_ratio: Option<U256>,
         compressed_size: Option<usize>,
     ) -> Self {
-        Self { base, rlp_bytes, compression_ratio, compressed_size }
+        Self {
+            base,
+            rlp_bytes,
+            compression_ratio,
+            compressed_size,
+            queue_index: None,
+            system_tx: false,
+        }
+    }
+
+    /// Returns the [`TxL1Message`] of the transaction, None if the transaction is not an L1
+    /// message with a queue index and a call target.
+    pub fn l1_message(&self) -> Option<TxL1Message> {
+        if !self.is_l1_msg() {
+            return None;
+        }
+        Some(TxL1Message {
+            queue_index: self.queue_index?,
+            gas_limit: self.gas_limit(),
+            to: self.kind().into_to()?,
+            value: self.value(),
+            sender: self.caller(),
+            input: self.input().clone(),
+        })
     }
 }
 
@@ -62,6 +98,8 @@ impl Default for ScrollTransaction<TxEnv> {
             rlp_bytes: None,
             compression_ratio: None,
             compressed_size: None,
+            queue_index: None,
+            system_tx: false,
         }
     }
 }
@@ -143,7 +181,8 @@ impl<T: Transaction> ScrollTxTr for ScrollTransaction<T> {
     }
 
     fn is_system_tx(&self) -> bool {
-        self.caller() == SYSTEM_ADDRESS
+        // the flag alone does not exempt a transaction from the rollup fee.
+        self.system_tx && self.caller() == SYSTEM_ADDRESS
     }
 
     fn rlp_bytes(&self) -> Option<&Bytes> {
@@ -157,6 +196,10 @@ impl<T: Transaction> ScrollTxTr for ScrollTransaction<T> {
     fn compressed_size(&self) -> Option<usize> {
         self.compressed_size
     }
+
+    fn queue_index(&self) -> Option<u64> {
+        self.queue_index
+    }
 }
 
 impl<TX: Transaction + SystemCallTx> SystemCallTx for ScrollTransaction<TX> {
@@ -167,11 +210,198 @@ impl<TX: Transaction + SystemCallTx> SystemCallTx for ScrollTransaction<TX> {
     ) -> Self {
         // System transactions do not require a rollup fee, as such we don't provide the RLP bytes
         // nor the compression ratio for it.
-        ScrollTransaction::new(
-            TX::new_system_tx_with_caller(caller, system_contract_address, data),
-            None,
-            None,
-            None,
-        )
+        let base = TX::new_system_tx_with_caller(caller, system_contract_address, data);
+        Self { system_tx: true, ..ScrollTransaction::new(base, None, None, None) }
+    }
+}
+
+/// An L1 message transaction, enqueued in the L1 message queue by the bridge.
+///
+/// L1 messages are encoded as `0x7E || rlp([queue_index, gas_limit, to, value, input, sender])`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxL1Message {
+    /// The index of the message in the L1 message queue.
+    pub queue_index: u64,
+    /// The gas limit of the message.
+    pub gas_limit: u64,
+    /// The target of the message, L1 messages cannot create contracts.
+    pub to: Address,
+    /// The value sent to the target.
+    pub value: U256,
+    /// The sender of the message, aliased if it is an L1 contract.
+    pub sender: Address,
+    /// The input of the message.
+    pub input: Bytes,
+}
+
+impl TxL1Message {
+    /// Returns the length of the RLP encoded fields of the message.
+    fn rlp_payload_length(&self) -> usize {
+        self.queue_index.length()
+            + self.gas_limit.length()
+            + self.to.length()
+            + self.value.length()
+            + self.input.length()
+            + self.sender.length()
+    }
+
+    /// Returns the length of the EIP-2718 encoding of the message.
+    pub fn encode_2718_len(&self) -> usize {
+        1 + self.length()
+    }
+
+    /// Encodes the message prefixed with its EIP-2718 type.
+    pub fn encode_2718(&self, out: &mut dyn BufMut) {
+        out.put_u8(L1_MESSAGE_TYPE);
+        self.encode(out);
+    }
+
+    /// Returns the EIP-2718 encoding of the message.
+    pub fn encoded_2718(&self) -> Bytes {
+        let mut out = Vec::with_capacity(self.encode_2718_len());
+        self.encode_2718(&mut out);
+        out.into()
+    }
+
+    /// Decodes a message from its EIP-2718 encoding.
+    pub fn decode_2718(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        match buf.split_first() {
+            Some((&L1_MESSAGE_TYPE, rest)) => {
+                *buf = rest;
+                Self::decode(buf)
+            }
+            Some(_) => Err(alloy_rlp::Error::Custom("unexpected transaction type")),
+            None => Err(alloy_rlp::Error::InputTooShort),
+        }
+    }
+
+    /// Returns the hash of the message, the keccak256 of its EIP-2718 encoding.
+    pub fn tx_hash(&self) -> B256 {
+        keccak256(self.encoded_2718())
+    }
+}
+
+impl Encodable for TxL1Message {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.rlp_payload_length() }.encode(out);
+        self.queue_index.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.encode(out);
+        self.sender.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.rlp_payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for TxL1Message {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        if buf.len() < header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+
+        let remaining = buf.len();
+        let tx = Self {
+            queue_index: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            sender: Decodable::decode(buf)?,
+        };
+        let consumed = remaining - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            });
+        }
+        Ok(tx)
+    }
+}
+
+impl From<TxL1Message> for ScrollTransaction<TxEnv> {
+    fn from(tx: TxL1Message) -> Self {
+        // L1 messages do not pay a rollup fee, the RLP bytes are provided for the hashing of the
+        // message.
+        let rlp_bytes = tx.encoded_2718();
+        let base = TxEnv {
+            tx_type: L1_MESSAGE_TYPE,
+            caller: tx.sender,
+            gas_limit: tx.gas_limit,
+            gas_price: 0,
+            kind: TxKind::Call(tx.to),
+            value: tx.value,
+            data: tx.input,
+            chain_id: None,
+            ..Default::default()
+        };
+        Self {
+            base,
+            rlp_bytes: Some(rlp_bytes),
+            compression_ratio: None,
+            compressed_size: None,
+            queue_index: Some(tx.queue_index),
+            system_tx: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::hex;
+
+    fn l1_message() -> TxL1Message {
+        TxL1Message {
+            queue_index: 1,
+            gas_limit: 100_000,
+            to: address!("0x00000000000000000000000000000000000dead1"),
+            value: U256::from(10u64.pow(18)),
+            sender: address!("0x000000000000000000000000000000000000dead"),
+            input: Bytes::from_static(&[1, 2, 3, 4]),
+        }
+    }
+
+    #[test]
+    fn test_l1_message_rlp() {
+        let tx = l1_message();
+        let encoded = hex!("7ef83d01830186a09400000000000000000000000000000000000dead1880de0b6b3a7640000840102030494000000000000000000000000000000000000dead");
+
+        assert_eq!(tx.encoded_2718(), Bytes::copy_from_slice(&encoded));
+        assert_eq!(tx.encode_2718_len(), encoded.len());
+        assert_eq!(TxL1Message::decode_2718(&mut &encoded[..]), Ok(tx));
+
+        // the type prefix is required.
+        assert_eq!(
+            TxL1Message::decode_2718(&mut &encoded[1..]),
+            Err(alloy_rlp::Error::Custom("unexpected transaction type"))
+        );
+        // the payload must match the list length.
+        assert!(TxL1Message::decode_2718(&mut &encoded[..encoded.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_l1_message_into_scroll_transaction() {
+        let tx = ScrollTransaction::from(l1_message());
+
+        assert!(tx.is_l1_msg());
+        assert_eq!(ScrollTxTr::queue_index(&tx), Some(1));
+        assert_eq!(tx.caller(), l1_message().sender);
+        assert_eq!(tx.kind(), TxKind::Call(l1_message().to));
+        assert_eq!(tx.rlp_bytes, Some(l1_message().encoded_2718()));
+        assert_eq!(tx.l1_message(), Some(l1_message()));
+
+        // ordinary transactions are not L1 messages.
+        assert_eq!(ScrollTransaction::<TxEnv>::default().l1_message(), None);
     }
 }
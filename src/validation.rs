@@ -0,0 +1,444 @@
+//! Transaction validation rules of Scroll.
+//!
+//! The rules are applied by the [`ScrollHandler`], whose validation the [`ScrollTxValidator`]
+//! runs to admit transactions to the transaction pool without executing them, such that the pool
+//! and the block execution agree on the validity of transactions.
+
+use crate::{
+    alias::StrictL1Sender,
+    builder::ScrollBuilder,
+    chain::{MaxTxPayloadSize, ScrollChainContext},
+    error::ScrollInvalidTransaction,
+    handler::ScrollHandler,
+    l1block::L1BlockInfo,
+    quote::ScrollMaxCost,
+    rollup_fee::{RollupFeeModel, ScrollRollupFeeModel},
+    transaction::{ScrollTxTr, SYSTEM_ADDRESS},
+    ScrollSpecId,
+};
+
+use revm::{
+    bytecode::Bytecode,
+    context::{
+        result::{EVMError, InvalidTransaction},
+        BlockEnv, CfgEnv, TransactionType,
+    },
+    handler::{EthFrame, Handler},
+    interpreter::interpreter::EthInterpreter,
+    primitives::U256,
+    state::AccountInfo,
+    Context, Database, MainContext,
+};
+
+/// Validates the environment of the transaction: only system transactions can use the system
+/// address as caller and vice versa, and the transaction type and payload size must be supported
+/// by the spec. The payload size is only checked if a maximum payload size is provided.
+pub fn validate_tx_env<TX: ScrollTxTr>(
+    tx: &TX,
+    max_tx_payload_size: Option<&MaxTxPayloadSize>,
+    spec_id: ScrollSpecId,
+) -> Result<(), ScrollInvalidTransaction> {
+    // only system transactions may use the system address as caller.
+    if tx.caller() == SYSTEM_ADDRESS && !tx.is_system_tx() {
+        return Err(ScrollInvalidTransaction::SystemCallerNotAllowed);
+    }
+    if tx.is_system_tx() && tx.caller() != SYSTEM_ADDRESS {
+        return Err(ScrollInvalidTransaction::InvalidSystemTxCaller { caller: tx.caller() });
+    }
+    validate_tx_type(tx, spec_id)?;
+    match max_tx_payload_size {
+        Some(max_tx_payload_size) => validate_tx_payload_size(tx, max_tx_payload_size, spec_id),
+        None => Ok(()),
+    }
+}
+
+/// Validates the transaction type and blob fields of the transaction are supported by the spec.
+///
+/// Scroll supports legacy, EIP-2930 and L1 message transactions, EIP-1559 transactions from
+/// Curie and EIP-7702 transactions from Euclid. Blob transactions, and blob fields on any
+/// transaction, are rejected.
+pub fn validate_tx_type<TX: ScrollTxTr>(
+    tx: &TX,
+    spec_id: ScrollSpecId,
+) -> Result<(), ScrollInvalidTransaction> {
+    let tx_type = tx.tx_type();
+    let supported = match TransactionType::from(tx_type) {
+        TransactionType::Legacy | TransactionType::Eip2930 => true,
+        TransactionType::Eip1559 => spec_id.is_enabled_in(ScrollSpecId::CURIE),
+        TransactionType::Eip7702 => spec_id.is_enabled_in(ScrollSpecId::EUCLID),
+        _ => tx.is_l1_msg(),
+    };
+    if !supported {
+        return Err(ScrollInvalidTransaction::UnsupportedTxType { tx_type, spec_id });
+    }
+    if !tx.blob_versioned_hashes().is_empty() || tx.max_fee_per_blob_gas() != 0 {
+        return Err(ScrollInvalidTransaction::BlobFieldsNotSupported);
+    }
+    Ok(())
+}
+
+/// Validates the payload of the transaction does not exceed the maximum payload size of the spec.
+/// L1 messages are checked against the limits of the L1 message queue, and system transactions
+/// are exempt.
+pub fn validate_tx_payload_size<TX: ScrollTxTr>(
+    tx: &TX,
+    max_tx_payload_size: &MaxTxPayloadSize,
+    spec_id: ScrollSpecId,
+) -> Result<(), ScrollInvalidTransaction> {
+    if tx.is_system_tx() {
+        return Ok(());
+    }
+    if tx.is_l1_msg() {
+        let gas_limit = tx.gas_limit();
+        let max_gas_limit = max_tx_payload_size.l1_message_max_gas_limit;
+        if gas_limit > max_gas_limit {
+            return Err(ScrollInvalidTransaction::L1MessageGasLimitTooHigh {
+                gas_limit,
+                max_gas_limit,
+            });
+        }
+        let size = tx.input().len();
+        let max_size = MaxTxPayloadSize::l1_message_max_size(gas_limit);
+        if size > max_size {
+            return Err(ScrollInvalidTransaction::PayloadTooLarge { size, max_size });
+        }
+        return Ok(());
+    }
+    let Some(max_size) = max_tx_payload_size.for_spec(spec_id) else {
+        return Ok(());
+    };
+    let size = tx.rlp_bytes().map_or_else(|| tx.input().len(), |rlp_bytes| rlp_bytes.len());
+    if size > max_size {
+        return Err(ScrollInvalidTransaction::PayloadTooLarge { size, max_size });
+    }
+    Ok(())
+}
+
+/// EIP-3607: Validates the caller of the transaction has no deployed code. EOAs whose code is a
+/// valid delegation designation, i.e. `0xef0100 || address`, can originate transactions.
+pub fn validate_caller_code(code: Option<&Bytecode>) -> Result<(), InvalidTransaction> {
+    if code.is_some_and(|code| !code.is_empty() && !code.is_eip7702()) {
+        return Err(InvalidTransaction::RejectCallerWithCode);
+    }
+    Ok(())
+}
+
+/// Validates the balance of the caller covers the fee.
+pub fn validate_caller_balance(fee: U256, balance: U256) -> Result<(), InvalidTransaction> {
+    if fee > balance {
+        return Err(InvalidTransaction::LackOfFundForMaxFee {
+            fee: fee.into(),
+            balance: balance.into(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates the sender of the L1 message against its L2 account.
+///
+/// L1 messages are not subject to the nonce check, and from Euclid not to the balance check
+/// either: an L1 message whose sender cannot afford the value reverts with `OutOfFunds` in the
+/// first frame, but is still included in the block.
+pub fn validate_l1_message_caller<TX: ScrollTxTr>(
+    tx: &TX,
+    account: &AccountInfo,
+    strict_l1_sender: Option<&StrictL1Sender>,
+    spec_id: ScrollSpecId,
+    is_eip3607_disabled: bool,
+) -> Result<(), ScrollInvalidTransaction> {
+    // In strict mode, reject senders which are unaliased L1 contracts or have code on L2 with a
+    // dedicated error.
+    if let Some(strict_l1_sender) = strict_l1_sender {
+        strict_l1_sender.validate(tx.caller(), account.code.as_ref())?;
+    }
+
+    if !spec_id.is_enabled_in(ScrollSpecId::EUCLID) {
+        validate_caller_balance(tx.max_balance_spending()?, account.balance)?;
+    }
+
+    // We check the sender of the L1 message is a EOA on the L2.
+    // If the sender is a (delegated) EOA on the L1, it should be a (delegated) EOA on the L2.
+    // If the sender is a contract on the L1, address aliasing assures with high probability that
+    // the L2 sender would be an EOA.
+    if !is_eip3607_disabled {
+        validate_caller_code(account.code.as_ref())?;
+    }
+    Ok(())
+}
+
+/// The read-only validator of transactions for admission to the transaction pool.
+///
+/// The validator runs the validation and the pre-execution of the [`ScrollHandler`] against the block the transaction is
+/// validated for, on a journal over the database which is discarded afterwards. The pool and the
+/// block execution therefore apply the same checks, including the Ethereum environment checks,
+/// the intrinsic gas, the configuration flags and the [`L1BlockInfoPolicy`], and never disagree on
+/// the validity of a transaction.
+///
+/// [`L1BlockInfoPolicy`]: crate::l1block::L1BlockInfoPolicy
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScrollTxValidator<F = ScrollRollupFeeModel> {
+    /// The configuration of the chain, e.g. the rollup fee model, the policy for the L1 block
+    /// information and the maximum payload size of transactions.
+    pub chain: ScrollChainContext<F>,
+    /// The configuration the transactions are validated for, e.g. the spec and the chain id.
+    pub cfg: CfgEnv<ScrollSpecId>,
+}
+
+impl<F: RollupFeeModel> ScrollTxValidator<F> {
+    /// Returns a new [`ScrollTxValidator`] for the spec using the provided rollup fee model, with
+    /// the EIPs of the spec activated as done by the default Scroll context.
+    pub fn new(fee_model: F, spec_id: ScrollSpecId) -> Self {
+        let mut cfg = CfgEnv::new_with_spec(spec_id);
+        cfg.enable_eip7702 = spec_id.is_enabled_in(ScrollSpecId::EUCLID);
+        cfg.enable_eip7623 = spec_id.is_enabled_in(ScrollSpecId::FEYNMAN);
+        Self { chain: ScrollChainContext::with_fee_model(fee_model), cfg }
+    }
+
+    /// Returns a new [`ScrollTxValidator`] using the configuration of the chain and the EVM.
+    pub fn from_chain(chain: &ScrollChainContext<F>, cfg: CfgEnv<ScrollSpecId>) -> Self {
+        Self { chain: chain.clone(), cfg }
+    }
+
+    /// Validates the transaction for inclusion in the block against the state of the database,
+    /// computing its rollup fee from the provided [`L1BlockInfo`]. Returns the worst-case cost of
+    /// the transaction.
+    ///
+    /// The database is only read from.
+    pub fn validate<TX: ScrollTxTr + Clone, DB: Database>(
+        &self,
+        tx: &TX,
+        db: &mut DB,
+        block: &BlockEnv,
+        l1_block_info: &L1BlockInfo,
+    ) -> Result<ScrollMaxCost, EVMError<DB::Error, ScrollInvalidTransaction>> {
+        let ctx = Context::mainnet()
+            .with_tx(tx.clone())
+            .with_block(block.clone())
+            .with_cfg(self.cfg.clone())
+            .with_chain(self.chain.clone())
+            .with_db(db);
+        let mut evm = ctx.build_scroll();
+        evm.0.ctx.chain.cache_l1_block_info(l1_block_info.clone(), block.number, self.cfg.spec);
+        let handler = ScrollHandler::<
+            _,
+            EVMError<DB::Error, ScrollInvalidTransaction>,
+            EthFrame<EthInterpreter>,
+        >::new();
+        handler.validate(&mut evm)?;
+        handler.pre_execution(&mut evm)?;
+
+        let execution_fee =
+            U256::from(tx.gas_limit()).saturating_mul(U256::from(tx.max_fee_per_gas()));
+        let l1_fee = evm.tx_fees().map_or(U256::ZERO, |tx_fees| tx_fees.l1_fee);
+        Ok(ScrollMaxCost { execution_fee, value: tx.value(), l1_fee })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        l1block::{L1BlockInfoFinding, L1BlockInfoFindingAction, L1BlockInfoPolicy},
+        test_utils::{
+            context, l1_block_info, ScrollContextTestUtils, CALLER, L1_DATA_COST,
+            MIN_TRANSACTION_COST,
+        },
+        transaction::L1_MESSAGE_TYPE,
+        ScrollTransaction,
+    };
+    use std::boxed::Box;
+
+    use revm::{
+        context::{ContextTr, TxEnv},
+        database::InMemoryDB,
+        primitives::{address, bytes},
+    };
+    use rstest::rstest;
+
+    fn validator() -> ScrollTxValidator {
+        ScrollTxValidator::new(ScrollRollupFeeModel, ScrollSpecId::CURIE)
+    }
+
+    fn with_code(db: &mut InMemoryDB, code: Bytecode) {
+        db.insert_account_info(
+            CALLER,
+            AccountInfo {
+                balance: MIN_TRANSACTION_COST + L1_DATA_COST,
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_validate() -> Result<(), Box<dyn core::error::Error>> {
+        let mut ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
+        let (tx, block) = (ctx.tx.clone(), ctx.block.clone());
+
+        let cost = validator().validate(&tx, ctx.db_mut(), &block, &l1_block_info())?;
+        assert_eq!(
+            cost,
+            ScrollMaxCost {
+                execution_fee: MIN_TRANSACTION_COST,
+                value: U256::ZERO,
+                l1_fee: L1_DATA_COST
+            }
+        );
+
+        // the caller must cover the rollup fee.
+        let mut ctx = context().with_funds(MIN_TRANSACTION_COST);
+        assert_eq!(
+            validator().validate(&tx, ctx.db_mut(), &block, &l1_block_info()),
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee {
+                    fee: Box::new(L1_DATA_COST),
+                    balance: Box::new(U256::ZERO),
+                }
+                .into()
+            ))
+        );
+
+        let mut ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
+        let mut future_tx = tx.clone();
+        future_tx.base.nonce = 1;
+        assert_eq!(
+            validator().validate(&future_tx, ctx.db_mut(), &block, &l1_block_info()),
+            Err(EVMError::Transaction(InvalidTransaction::NonceTooHigh { tx: 1, state: 0 }.into()))
+        );
+
+        // EIP-3607, delegated EOAs can originate transactions.
+        with_code(ctx.db_mut(), Bytecode::new_raw(bytes!("6000")));
+        assert_eq!(
+            validator().validate(&tx, ctx.db_mut(), &block, &l1_block_info()),
+            Err(EVMError::Transaction(InvalidTransaction::RejectCallerWithCode.into()))
+        );
+        with_code(
+            ctx.db_mut(),
+            Bytecode::new_eip7702(address!("0x0000000000000000000000000000000000000007")),
+        );
+        validator().validate(&tx, ctx.db_mut(), &block, &l1_block_info())?;
+
+        // L1 messages do not pay a rollup fee and are not subject to the nonce check.
+        let mut l1_message = future_tx;
+        l1_message.base.tx_type = L1_MESSAGE_TYPE;
+        l1_message.base.gas_price = 0;
+        let mut ctx = context();
+        let cost = validator().validate(&l1_message, ctx.db_mut(), &block, &l1_block_info())?;
+        assert_eq!(cost.l1_fee, U256::ZERO);
+
+        // the policy of the chain applies to the provided L1 block information.
+        let mut ctx = context().with_funds(MIN_TRANSACTION_COST + L1_DATA_COST);
+        let zero_scalar = L1BlockInfo { l1_commit_scalar: Some(U256::ZERO), ..l1_block_info() };
+        validator().validate(&tx, ctx.db_mut(), &block, &zero_scalar)?;
+        let mut strict = validator();
+        strict.chain.l1_block_info_policy =
+            L1BlockInfoPolicy::uniform(L1BlockInfoFindingAction::Reject);
+        let err = strict.validate(&tx, ctx.db_mut(), &block, &zero_scalar).unwrap_err();
+        assert!(matches!(
+            err,
+            EVMError::Transaction(ScrollInvalidTransaction::InvalidL1BlockInfo(
+                L1BlockInfoFinding::ZeroScalar { .. }
+            ))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_eip1559() -> Result<(), Box<dyn core::error::Error>> {
+        // the caller covers the maximum execution fee and value, and the rollup fee from the
+        // balance left after the debit at the effective gas price of 6.
+        let funds = U256::from(21_000 * 10 + 1_000);
+        let mut ctx = context().with_funds(funds).modify_block_chained(|block| block.basefee = 5);
+        let mut tx = ctx.tx.clone();
+        eip1559(&mut tx);
+        let block = ctx.block.clone();
+
+        let cost = validator().validate(&tx, ctx.db_mut(), &block, &l1_block_info())?;
+        assert_eq!(
+            cost,
+            ScrollMaxCost {
+                execution_fee: U256::from(21_000 * 10),
+                value: U256::from(1_000),
+                l1_fee: L1_DATA_COST
+            }
+        );
+
+        Ok(())
+    }
+
+    fn legacy(_tx: &mut ScrollTransaction<TxEnv>) {}
+
+    fn eip1559(tx: &mut ScrollTransaction<TxEnv>) {
+        tx.base.tx_type = TransactionType::Eip1559 as u8;
+        tx.base.gas_price = 10;
+        tx.base.gas_priority_fee = Some(1);
+        tx.base.value = U256::from(1_000);
+    }
+
+    fn eip1559_priority_fee_above_max_fee(tx: &mut ScrollTransaction<TxEnv>) {
+        eip1559(tx);
+        tx.base.gas_priority_fee = Some(20);
+    }
+
+    fn below_intrinsic_gas(tx: &mut ScrollTransaction<TxEnv>) {
+        tx.base.gas_limit = 20_000;
+    }
+
+    fn other_chain(tx: &mut ScrollTransaction<TxEnv>) {
+        tx.base.chain_id = Some(2);
+    }
+
+    fn future_nonce(tx: &mut ScrollTransaction<TxEnv>) {
+        tx.base.nonce = 1;
+    }
+
+    fn default_cfg(_cfg: &mut CfgEnv<ScrollSpecId>) {}
+
+    fn no_nonce_check(cfg: &mut CfgEnv<ScrollSpecId>) {
+        cfg.disable_nonce_check = true;
+    }
+
+    #[rstest]
+    #[case(61_000, 0, legacy, default_cfg)]
+    #[case(60_999, 0, legacy, default_cfg)]
+    #[case(21_000, 0, legacy, default_cfg)]
+    #[case(211_000, 5, eip1559, default_cfg)]
+    #[case(210_999, 5, eip1559, default_cfg)]
+    #[case(211_000, 9, eip1559, default_cfg)]
+    #[case(211_000, 20, eip1559, default_cfg)]
+    #[case(1_000_000, 5, eip1559_priority_fee_above_max_fee, default_cfg)]
+    #[case(61_000, 0, below_intrinsic_gas, default_cfg)]
+    #[case(61_000, 0, other_chain, default_cfg)]
+    #[case(61_000, 0, future_nonce, default_cfg)]
+    #[case(61_000, 0, future_nonce, no_nonce_check)]
+    fn test_validate_agrees_with_handler(
+        #[case] funds: u64,
+        #[case] basefee: u64,
+        #[case] modify_tx: fn(&mut ScrollTransaction<TxEnv>),
+        #[case] modify_cfg: fn(&mut CfgEnv<ScrollSpecId>),
+    ) -> Result<(), Box<dyn core::error::Error>> {
+        let mut ctx = context()
+            .with_funds(U256::from(funds))
+            .modify_block_chained(|block| block.basefee = basefee)
+            .modify_cfg_chained(|cfg| {
+                cfg.spec = ScrollSpecId::CURIE;
+                modify_cfg(cfg)
+            })
+            .modify_tx_chained(modify_tx);
+        let (tx, block) = (ctx.tx.clone(), ctx.block.clone());
+        let validator = ScrollTxValidator::from_chain(&ctx.chain, ctx.cfg.clone());
+        let l1_block_info = L1BlockInfo::try_fetch(ctx.db_mut(), ScrollSpecId::CURIE)?;
+        let admitted = validator.validate(&tx, ctx.db_mut(), &block, &l1_block_info).map(|_| ());
+
+        let mut evm = ctx.build_scroll();
+        let handler = ScrollHandler::<_, EVMError<_, ScrollInvalidTransaction>, EthFrame<_>>::new();
+        let executed =
+            handler.validate(&mut evm).and_then(|_| handler.pre_execution(&mut evm)).map(|_| ());
+
+        assert_eq!(admitted, executed);
+
+        Ok(())
+    }
+}